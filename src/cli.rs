@@ -0,0 +1,1117 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use clap::{Parser, Subcommand};
+use crate::upbit::{init_keystore, load_account, spawn_yipir_upbit_service, UpbitAccount};
+use crate::upbit::api::{CandleUnit, buy_market_order, get_all_balances, get_all_markets, get_all_tickers, get_balance_of, get_deposits, get_orderbook, get_orders, get_recent_trade_ticks, get_tickers, get_wallet_status, get_withdraw_chance, get_withdraws, guaranteed_get_candle_data, sell_market_order, withdraw_coin, withdraw_krw};
+use crate::upbit::api_key::{inspect_api_key, KeyPermission, KeyReport};
+use crate::upbit::backtest::{run_backtest, BacktestSide};
+use crate::upbit::config::ServiceConfig;
+use crate::upbit::control::spawn_control_server;
+use crate::upbit::executor::{OrderExecutor, PaperAccount};
+use crate::upbit::journal::TradeJournal;
+use crate::upbit::market_filter::MarketFilter;
+use crate::upbit::portfolio::fetch_portfolio;
+use crate::upbit::quote::{fetch_quote_rates, QuoteCurrency};
+use crate::upbit::reconcile::reconcile;
+use crate::upbit::response::{CandleDataOperation, Orderbook, TradeSide, Transfer};
+use crate::upbit::risk::{ExitRules, RiskLimits, TrailingStop};
+use crate::upbit::scanner::{scan_candles, ScanOptions};
+use crate::upbit::scoring::{rank_buy_candidates, ScoreWeights, ScoringConfig};
+use crate::upbit::sizing::{KellyStats, PositionSizing, StopDistance, VolatilityMeasure};
+use crate::upbit::trade_flow::{FlowConditions, TradeFlow};
+use crate::upbit::shutdown::wait_for_signal;
+use crate::upbit::status::ServiceState;
+use crate::upbit::ops::{BandOptions, MacdOptions, SarOptions};
+use crate::upbit::strategy::{is_sell_signal, Confirmations};
+use crate::upbit::wallet::{suspended_currencies, CoinWithdrawal, WithdrawalAllowlist};
+
+#[derive(Parser)]
+#[command(name = "yipir", about = "업비트 RSI 다이버전스 자동매매 봇")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(clap::Args)]
+pub struct ServiceOptions {
+    /// 상태 조회 및 제어 API를 열 주소 (예: 127.0.0.1:8080)
+    #[arg(long)]
+    http: Option<String>,
+    /// 거래 일지 파일 경로
+    #[arg(long, default_value = "yipir-journal.jsonl")]
+    journal: PathBuf,
+    /// 종료 시 진행 중인 주문 작업을 기다릴 최대 시간(초)
+    #[arg(long, default_value_t = 30)]
+    shutdown_timeout: u64,
+    /// 종료 시 미체결 지정가 주문을 취소합니다. (실거래 모드 전용)
+    #[arg(long)]
+    cancel_open_orders: bool,
+    /// API 키가 이 기간(일) 안에 만료되면 실행하지 않습니다. (실거래 모드 전용)
+    #[arg(long, default_value_t = 7)]
+    min_key_validity_days: u64,
+    /// 스캔 시 동시에 요청할 종목 수
+    #[arg(long, default_value_t = 8)]
+    scan_concurrency: usize,
+    /// 한 종목의 캔들 데이터를 기다릴 최대 시간(초)
+    #[arg(long, default_value_t = 5)]
+    market_timeout: u64,
+    /// 평균 매수가 대비 이 비율(%)만큼 하락하면 손절합니다.
+    #[arg(long)]
+    stop_loss: Option<f64>,
+    /// 평균 매수가 대비 이 비율(%)만큼 상승하면 익절합니다.
+    #[arg(long)]
+    take_profit: Option<f64>,
+    /// 보유 중 최고가 대비 이 비율(%)만큼 하락하면 청산합니다.
+    #[arg(long, conflicts_with_all = ["trailing_atr", "trailing_sar"])]
+    trailing_stop: Option<f64>,
+    /// 보유 중 최고가에서 ATR의 이 배수만큼 하락하면 청산합니다.
+    #[arg(long, conflicts_with = "trailing_sar")]
+    trailing_atr: Option<f64>,
+    /// 진입 후 상승 추세였던 파라볼릭 SAR이 하락 추세로 뒤집히면 청산합니다. 진입할 때 이미 하락 추세였다면 상승 추세로 돌아선 뒤부터 적용합니다.
+    #[arg(long)]
+    trailing_sar: bool,
+    /// 파라볼릭 SAR 가속 계수의 시작값이자 증가폭
+    #[arg(long, default_value_t = 0.02)]
+    sar_step: f64,
+    /// 파라볼릭 SAR 가속 계수의 최대값
+    #[arg(long, default_value_t = 0.2)]
+    sar_max: f64,
+    /// 이 시간(분)보다 오래 보유한 종목은 청산합니다.
+    #[arg(long)]
+    max_holding: Option<u64>,
+    /// 동시에 보유할 최대 종목 수
+    #[arg(long)]
+    max_positions: Option<usize>,
+    /// 한 종목에 투입할 최대 금액 (호가 통화 기준)
+    #[arg(long)]
+    max_market_exposure: Option<f64>,
+    /// 전체 보유 종목에 투입할 최대 금액 (호가 통화 기준)
+    #[arg(long)]
+    max_total_exposure: Option<f64>,
+    /// 일일 손실이 이 금액(KRW) 이상이면 새 매수를 멈춥니다.
+    #[arg(long)]
+    daily_loss_limit: Option<f64>,
+    /// 일일 손실이 이 금액(KRW) 이상이면 모든 보유 종목을 청산하고 매매를 멈춥니다.
+    #[arg(long)]
+    kill_switch_loss: Option<f64>,
+    /// 분당 최대 매수 주문 수 (매도 주문은 제한하지 않음)
+    #[arg(long)]
+    max_orders_per_minute: Option<usize>,
+    /// 호가로 추정한 시장가 매수의 슬리피지(%)가 이보다 크거나 호가 잔량이 모자라면 매수하지 않습니다.
+    #[arg(long)]
+    max_slippage: Option<f64>,
+    /// 거래 일지에 없는 보유 종목을 관리하지 않습니다. (기본값은 관리 대상으로 받아들임)
+    #[arg(long)]
+    ignore_unknown_positions: bool,
+    /// 한 번의 스캔에서 매수할 최대 종목 수 (점수 높은 순)
+    #[arg(long, default_value_t = 3)]
+    top_n: usize,
+    #[command(flatten)]
+    weights: WeightOptions,
+    #[command(flatten)]
+    sizing: SizingOptions,
+    #[command(flatten)]
+    flow: FlowOptions,
+    #[command(flatten)]
+    confirmations: ConfirmationOptions,
+    #[command(flatten)]
+    markets: MarketOptions,
+}
+
+#[derive(clap::Args)]
+pub struct MarketOptions {
+    /// 매매할 마켓의 호가 통화
+    #[arg(long, value_enum, default_value_t = QuoteCurrency::Krw)]
+    quote: QuoteCurrency,
+    /// 투자 유의/유의 종목도 매수 대상에 포함합니다.
+    #[arg(long)]
+    include_warning: bool,
+    /// 주의 종목(가격 급등락, 거래량 급등 등)을 매수 대상에서 제외합니다.
+    #[arg(long)]
+    exclude_caution: bool,
+    /// 최근 24시간 거래대금이 이 금액 미만인 마켓은 매수하지 않습니다. (호가 통화 기준)
+    #[arg(long)]
+    min_trade_value: Option<f64>,
+    /// 이 마켓들만 매수합니다. (쉼표로 구분, 마켓 코드나 통화, 예: KRW-BTC,ETH)
+    #[arg(long, value_delimiter = ',')]
+    whitelist: Vec<String>,
+    /// 이 마켓들은 매수하지 않습니다. (쉼표로 구분)
+    #[arg(long, value_delimiter = ',')]
+    blacklist: Vec<String>,
+    /// 입출금이 중단된 통화도 매수 대상에 포함합니다.
+    #[arg(long)]
+    include_suspended_wallets: bool,
+}
+
+impl MarketOptions {
+    fn filter(&self) -> MarketFilter {
+        MarketFilter {
+            quote: self.quote,
+            exclude_warning: !self.include_warning,
+            exclude_caution: self.exclude_caution,
+            min_trade_value_24h: self.min_trade_value,
+            whitelist: self.whitelist.clone(),
+            blacklist: self.blacklist.clone(),
+            exclude_suspended_wallets: !self.include_suspended_wallets,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct FlowOptions {
+    /// 체결 흐름을 계산할 최근 체결 수
+    #[arg(long, default_value_t = 200)]
+    flow_ticks: usize,
+    /// 평균 체결량의 이 배수 이상인 체결을 대량 체결로 봅니다.
+    #[arg(long, default_value_t = 10.0)]
+    large_trade_multiple: f64,
+    /// 최근 체결의 (매수 체결량 - 매도 체결량) / 전체 체결량이 이 값 이상일 때만 매수합니다. (-1 ~ 1)
+    #[arg(long)]
+    min_buy_delta: Option<f64>,
+    /// 보유 종목의 최근 체결에서 (매도 체결량 - 매수 체결량) / 전체 체결량이 이 값 이상이면 매도합니다. (0 ~ 1)
+    #[arg(long)]
+    sell_delta: Option<f64>,
+}
+
+impl FlowOptions {
+    fn conditions(&self) -> FlowConditions {
+        FlowConditions {
+            ticks: self.flow_ticks,
+            large_trade_multiple: self.large_trade_multiple,
+            min_buy_delta_ratio: self.min_buy_delta,
+            sell_delta_ratio: self.sell_delta,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct ConfirmationOptions {
+    /// MACD 빠른 EMA 기간
+    #[arg(long, default_value_t = 12, value_parser = clap::value_parser!(u16).range(1..))]
+    macd_fast: u16,
+    /// MACD 느린 EMA 기간
+    #[arg(long, default_value_t = 26, value_parser = clap::value_parser!(u16).range(1..))]
+    macd_slow: u16,
+    /// MACD 시그널 선 기간
+    #[arg(long, default_value_t = 9, value_parser = clap::value_parser!(u16).range(1..))]
+    macd_signal: u16,
+    /// 이 캔들 수 이내의 마지막 MACD 교차가 골든 크로스일 때만 매수합니다.
+    #[arg(long)]
+    macd_cross_within: Option<usize>,
+    /// MACD 히스토그램 저점 다이버전스가 있을 때만 매수합니다.
+    #[arg(long)]
+    macd_divergence: bool,
+    /// 볼린저 밴드와 켈트너 채널의 기간
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u16).range(2..))]
+    band_period: u16,
+    /// 볼린저 밴드 폭 (표준편차의 배수)
+    #[arg(long, default_value_t = 2.0)]
+    band_std: f64,
+    /// 켈트너 채널 폭 (ATR의 배수)
+    #[arg(long, default_value_t = 1.5)]
+    keltner_atr: f64,
+    /// 가격이 볼린저 밴드 하단보다 낮을 때만 매수합니다.
+    #[arg(long)]
+    below_lower_band: bool,
+    /// 볼린저 밴드가 켈트너 채널 안으로 수축한 동안에는 매수하지 않습니다.
+    #[arg(long)]
+    skip_squeeze: bool,
+    /// ADX, +DI, -DI 기간
+    #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(u16).range(1..))]
+    dmi_period: u16,
+    /// ADX가 이 값 이상이고 -DI가 +DI보다 큰 강한 하락 추세에서는 매수하지 않습니다. (0 ~ 100, 보통 25)
+    #[arg(long)]
+    downtrend_adx: Option<f64>,
+    /// 상대 거래량을 계산할 직전 캔들 수
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u16).range(1..))]
+    volume_lookback: u16,
+    /// 최근 5개 캔들 중 거래량이 직전 평균의 이 배수 이상인 캔들이 있을 때만 매수합니다. (예: 2.0)
+    #[arg(long)]
+    volume_spike: Option<f64>,
+    /// 5개 캔들 전보다 OBV가 높을 때만 매수합니다.
+    #[arg(long)]
+    obv_rising: bool,
+    /// MFI 기간
+    #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(u16).range(1..))]
+    mfi_period: u16,
+    /// MFI가 이 값 이하일 때만 매수합니다. (0 ~ 100, 보통 20)
+    #[arg(long)]
+    max_mfi: Option<f64>,
+    /// 가격이 당일(한국 시간) VWAP보다 낮을 때만 매수합니다.
+    #[arg(long)]
+    below_vwap: bool,
+}
+
+impl ConfirmationOptions {
+    fn confirmations(&self) -> Confirmations {
+        Confirmations {
+            macd: MacdOptions {
+                fast: self.macd_fast as usize,
+                slow: self.macd_slow as usize,
+                signal: self.macd_signal as usize,
+            },
+            macd_cross_within: self.macd_cross_within,
+            macd_divergence: self.macd_divergence,
+            bands: BandOptions {
+                period: self.band_period as usize,
+                std_multiple: self.band_std,
+                atr_multiple: self.keltner_atr,
+            },
+            below_lower_band: self.below_lower_band,
+            skip_squeeze: self.skip_squeeze,
+            dmi_period: self.dmi_period as usize,
+            downtrend_adx: self.downtrend_adx,
+            volume_lookback: self.volume_lookback as usize,
+            volume_spike: self.volume_spike,
+            obv_rising: self.obv_rising,
+            mfi_period: self.mfi_period as usize,
+            max_mfi: self.max_mfi,
+            below_vwap: self.below_vwap,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct WeightOptions {
+    /// 매수 점수 가중치: RSI 다이버전스 크기
+    #[arg(long, default_value_t = 1.0)]
+    weight_divergence: f64,
+    /// 매수 점수 가중치: RSI가 30보다 낮은 정도
+    #[arg(long, default_value_t = 1.0)]
+    weight_rsi: f64,
+    /// 매수 점수 가중치: 가격이 EWM보다 낮은 정도
+    #[arg(long, default_value_t = 1.0)]
+    weight_ewm: f64,
+    /// 매수 점수 가중치: 거래량 급증
+    #[arg(long, default_value_t = 1.0)]
+    weight_volume: f64,
+}
+
+impl WeightOptions {
+    fn weights(&self) -> ScoreWeights {
+        ScoreWeights {
+            divergence: self.weight_divergence,
+            rsi: self.weight_rsi,
+            ewm: self.weight_ewm,
+            volume: self.weight_volume,
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct SizingOptions {
+    /// 매수 금액 결정 방식
+    #[arg(long, value_enum, default_value_t = SizingMethod::Fixed)]
+    sizing: SizingMethod,
+    /// fixed: 매수에 사용할 호가 통화 잔고 비율
+    #[arg(long, default_value_t = 0.2)]
+    fraction: f64,
+    /// volatility: 현재가 대비 목표 변동성(%)
+    #[arg(long, default_value_t = 1.0)]
+    target_volatility: f64,
+    /// volatility: 변동성 측정 방식
+    #[arg(long, value_enum, default_value_t = VolatilitySource::Std)]
+    volatility_measure: VolatilitySource,
+    /// fixed-risk: 한 거래에서 감수할 평가액 대비 손실(%)
+    #[arg(long, default_value_t = 1.0)]
+    risk_per_trade: f64,
+    /// fixed-risk: 손절 폭(%), 지정하지 않으면 --stop-loss, --trailing-stop, --trailing-atr 순서로 사용합니다.
+    #[arg(long)]
+    risk_stop: Option<f64>,
+    /// kelly: 켈리 비율 중 실제로 사용할 비율
+    #[arg(long, default_value_t = 0.5)]
+    kelly_fraction: f64,
+    /// kelly: 승률과 손익비를 구할 백테스트 종목
+    #[arg(long, default_value = "KRW-BTC")]
+    kelly_market: String,
+    /// ATR 기반 추적 손절과 변동성 계산에 사용할 ATR 기간
+    #[arg(long, default_value_t = 14, value_parser = clap::value_parser!(u16).range(1..))]
+    atr_period: u16,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum SizingMethod {
+    /// 호가 통화 잔고의 일정 비율
+    Fixed,
+    /// 변동성에 반비례
+    Volatility,
+    /// 손절 폭 대비 고정 위험
+    FixedRisk,
+    /// 백테스트 통계로 구한 켈리 비율
+    Kelly,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum VolatilitySource {
+    Std,
+    Atr,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 실거래 모드로 봇을 실행합니다.
+    Run {
+        #[command(flatten)]
+        options: ServiceOptions,
+    },
+    /// 실제 주문 없이 모의 계좌로 봇을 실행합니다.
+    Paper {
+        /// 모의 계좌의 시작 원화 잔고, 호가 통화가 KRW가 아니면 시작할 때 현재 환산 비율로 호가 통화로 바꿉니다.
+        #[arg(long, default_value_t = 1_000_000.0)]
+        krw: f64,
+        #[command(flatten)]
+        options: ServiceOptions,
+    },
+    /// 과거 캔들 데이터로 매수/매도 조건을 검증합니다.
+    Backtest {
+        market: String,
+        #[arg(default_value = "1m")]
+        unit: CandleUnit,
+        /// 불러올 캔들 개수 (최대 200)
+        #[arg(long, default_value_t = 200)]
+        count: u8,
+        /// 한 번의 판단에 사용할 캔들 개수 (15 이상)
+        #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u16).range(15..))]
+        window: u16,
+        /// 시작 잔고 (마켓의 호가 통화 기준), 생략하면 1,000,000 KRW를 현재 환산 비율로 바꾼 금액
+        #[arg(long, alias = "krw")]
+        cash: Option<f64>,
+        #[command(flatten)]
+        sizing: SizingOptions,
+        #[command(flatten)]
+        confirmations: ConfirmationOptions,
+    },
+    /// 암호화된 키스토어 파일을 관리합니다.
+    Keystore {
+        #[command(subcommand)]
+        command: KeystoreCommand,
+    },
+    /// 계좌 잔고를 출력합니다.
+    Balances,
+    /// 설정된 API 키의 만료 시각과 권한을 출력합니다.
+    ///
+    /// 권한은 효과가 없는 요청을 보내 확인합니다: 계좌 잔고 조회(자산 조회), 임의 UUID의 주문 취소(주문하기),
+    /// 임의 UUID의 출금 취소(출금하기). 없는 주문과 출금을 가리키므로 실제로 취소되는 것은 없으며,
+    /// out_of_scope로 거절되면 권한 없음, order_not_found/withdraw_not_found로 거절되면 권한 있음으로 판단합니다.
+    ApiKey,
+    /// 보유 종목 평가액과 실현/미실현 손익을 출력합니다.
+    Portfolio {
+        /// 실현 손익 계산에 사용할 거래 일지 파일 경로
+        #[arg(long, default_value = "yipir-journal.jsonl")]
+        journal: PathBuf,
+    },
+    /// 주문 목록을 출력합니다.
+    Orders {
+        /// wait, watch, done, cancel 중 하나
+        #[arg(long, default_value = "wait")]
+        state: String,
+    },
+    /// 마켓 목록과 유의/주의 종목 지정 여부를 출력합니다. 필터 옵션을 주면 매수 대상 마켓만 출력합니다.
+    Markets {
+        #[command(flatten)]
+        markets: MarketOptions,
+    },
+    /// 현재가와 전일 대비 변화, 24시간 거래대금, 52주 최고/최저가를 출력합니다. 종목을 생략하면 원화 마켓 전체를 조회합니다.
+    Tickers {
+        markets: Vec<String>,
+    },
+    /// 최근 체결의 매수/매도 체결량, delta, 대량 체결을 출력합니다.
+    Trades {
+        market: String,
+        /// 조회할 최근 체결 수
+        #[arg(long, default_value_t = 200)]
+        ticks: usize,
+        /// 평균 체결량의 이 배수 이상인 체결을 대량 체결로 봅니다.
+        #[arg(long, default_value_t = 10.0)]
+        large_trade_multiple: f64,
+    },
+    /// 캔들 데이터를 출력합니다.
+    Candles {
+        market: String,
+        unit: CandleUnit,
+        #[arg(long, default_value_t = 20)]
+        count: u8,
+    },
+    /// 호가의 스프레드, 잔량 비율, 중간가 근처 잔량을 출력합니다. --amount를 주면 시장가 매수 체결 예상도 출력합니다.
+    Orderbook {
+        market: String,
+        /// 잔량을 합산할 중간가 대비 범위(%)
+        #[arg(long, default_value_t = 1.0)]
+        depth: f64,
+        /// 이 금액(호가 통화 기준)만큼 시장가 매수할 때의 체결 예상을 출력합니다.
+        #[arg(long)]
+        amount: Option<f64>,
+    },
+    /// 시장가 매수 주문을 보냅니다.
+    Buy {
+        market: String,
+        /// 매수에 사용할 금액 (마켓의 호가 통화 기준)
+        amount: f64,
+        /// 확인 없이 바로 주문합니다.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 시장가 매도 주문을 보냅니다.
+    Sell {
+        market: String,
+        /// 보유량 중 매도할 비율(%)
+        #[arg(long, default_value_t = 100.0)]
+        ratio: f64,
+        /// 확인 없이 바로 주문합니다.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 입금 내역을 출력합니다.
+    Deposits {
+        /// 이 통화의 입금 내역만 출력합니다.
+        #[arg(long)]
+        currency: Option<String>,
+    },
+    /// 출금 내역을 출력합니다.
+    Withdraws {
+        /// 이 통화의 출금 내역만 출력합니다.
+        #[arg(long)]
+        currency: Option<String>,
+    },
+    /// 출금 수수료, 지갑 상태, 출금 한도를 출력합니다.
+    WithdrawChance {
+        currency: String,
+        /// 출금 네트워크, 생략하면 통화 코드와 같은 네트워크
+        #[arg(long)]
+        net_type: Option<String>,
+    },
+    /// 코인을 출금합니다. YIPIR_WITHDRAW_ALLOWLIST 파일에 있는 주소로만 보낼 수 있습니다.
+    Withdraw {
+        currency: String,
+        amount: f64,
+        address: String,
+        /// 출금 네트워크, 생략하면 통화 코드와 같은 네트워크
+        #[arg(long)]
+        net_type: Option<String>,
+        /// 데스티네이션 태그나 메모
+        #[arg(long)]
+        secondary_address: Option<String>,
+        /// 확인 없이 바로 출금합니다.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 등록된 출금 계좌로 원화를 출금합니다. YIPIR_WITHDRAW_ALLOWLIST 파일에서 allow_krw를 켜야 합니다.
+    WithdrawKrw {
+        amount: f64,
+        /// 2차 인증 수단 (kakao, naver, hana)
+        #[arg(long, default_value = "kakao")]
+        two_factor: String,
+        /// 확인 없이 바로 출금합니다.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 통화별 입출금 지갑 상태를 출력합니다.
+    Wallets {
+        /// 입출금이 중단된 지갑만 출력합니다.
+        #[arg(long)]
+        suspended: bool,
+    },
+    /// 현재 매수/매도 조건을 만족하는 종목을 출력합니다.
+    Signals {
+        #[arg(default_value = "1m")]
+        unit: CandleUnit,
+        #[command(flatten)]
+        weights: WeightOptions,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeystoreCommand {
+    /// UPBIT_ACCESS_KEY, UPBIT_SECRET_KEY의 키를 YIPIR_KEYSTORE_PASSPHRASE로 암호화하여 키스토어 파일을 만듭니다.
+    Init {
+        /// 만들 키스토어 파일 경로
+        path: PathBuf,
+        /// 이미 있는 파일을 덮어씁니다.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub async fn execute(cli: Cli) {
+    match cli.command {
+        Command::Run { options } => {
+            let account = require_account();
+            let report = require_key_report(&account).await;
+            if let Err(e) = report.check_live(Duration::from_secs(options.min_key_validity_days * 86_400)) {
+                eprintln!("{e} 실거래 모드로 실행하지 않습니다.");
+                std::process::exit(1);
+            }
+            let executor = OrderExecutor::Live(account);
+            run_service(executor, "live", options).await;
+        }
+        Command::Paper { krw, options } => {
+            let paper = match options.markets.quote {
+                QuoteCurrency::Krw => PaperAccount::new(krw),
+                quote => {
+                    let Some(rate) = fetch_quote_rates().await.ok().and_then(|rates| rates.rate(quote)) else {
+                        eprintln!("{}의 원화 가격을 불러올 수 없습니다.", quote.code());
+                        std::process::exit(1);
+                    };
+                    PaperAccount::with_cash(quote, krw / rate, rate)
+                }
+            };
+            let executor = OrderExecutor::Paper(Arc::new(Mutex::new(paper)));
+            run_service(executor, "paper", options).await;
+        }
+        Command::Backtest { market, unit, count, window, cash, sizing, confirmations } => {
+            let window = window as usize;
+            let candle_data = match guaranteed_get_candle_data(&market, unit, count).await {
+                Ok(candle_data) => candle_data,
+                Err(e) => {
+                    eprintln!("{market}의 캔들 데이터를 불러올 수 없습니다: {e}");
+                    std::process::exit(1);
+                }
+            };
+            if window > candle_data.len() {
+                eprintln!("window({window})가 불러온 캔들 개수({})보다 큽니다.", candle_data.len());
+                std::process::exit(1);
+            }
+
+            let confirmations = confirmations.confirmations();
+            let sizing = build_sizing(&sizing, None, &confirmations).await;
+            let quote = QuoteCurrency::of_market(&market);
+            let cash = match cash {
+                Some(cash) => cash,
+                None => fetch_quote_rates().await.ok()
+                    .and_then(|rates| rates.convert(1_000_000.0, QuoteCurrency::Krw, quote))
+                    .unwrap_or(1_000_000.0),
+            };
+            let report = run_backtest(&candle_data, window, cash, &sizing, &confirmations);
+            for trade in &report.trades {
+                let side = match trade.side {
+                    BacktestSide::Buy => "매수",
+                    BacktestSide::Sell => "매도",
+                };
+                println!("{} {side} {} @ {}", trade.candle_date_time_kst, trade.volume, trade.price);
+            }
+            println!("{} 거래 {}회, 최종 평가액 {} {} ({:+.2}%)",
+                     report.market, report.round_trip_returns.len(), report.final_equity, quote.code(), report.total_return());
+            if let Some(win_rate) = report.win_rate() {
+                println!("승률 {:.1}%", win_rate * 100.0);
+            }
+            if let Some(stats) = KellyStats::from_report(&report) {
+                println!("손익비 {:.2}, 켈리 비율 {:.1}%", stats.payoff_ratio, stats.kelly() * 100.0);
+            }
+        }
+        Command::Keystore { command: KeystoreCommand::Init { path, force } } => {
+            match init_keystore(&path, force) {
+                Ok(()) => println!("키스토어를 {}에 저장했습니다. YIPIR_KEYSTORE에 이 경로를 지정하세요.", path.display()),
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Balances => {
+            let account = require_account();
+            match get_all_balances(&account).await {
+                Ok(balances) => for balance in balances {
+                    println!("{:<8} 보유 {:>20} 주문중 {:>20} 평균매수가 {:>16}",
+                             balance.currency, balance.balance, balance.locked, balance.avg_buy_price);
+                },
+                Err(e) => eprintln!("잔고를 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::ApiKey => {
+            let account = require_account();
+            let report = require_key_report(&account).await;
+            if report.has(KeyPermission::Withdraw) {
+                println!("출금하기 권한이 있는 키입니다. 자동매매에는 출금 권한이 없는 키를 권장합니다.");
+            }
+        }
+        Command::Portfolio { journal } => {
+            let executor = OrderExecutor::Live(require_account());
+            let entries = match TradeJournal::open(&journal) {
+                Ok(journal) => journal.entries(),
+                Err(e) => {
+                    eprintln!("거래 일지 파일을 열 수 없습니다: {e}");
+                    Vec::new()
+                }
+            };
+            let portfolio = match fetch_portfolio(&executor, &entries).await {
+                Ok(portfolio) => portfolio,
+                Err(e) => {
+                    eprintln!("포트폴리오를 평가할 수 없습니다: {e}");
+                    std::process::exit(1);
+                }
+            };
+            for asset in &portfolio.assets {
+                println!("{:<12} 보유 {:>16} 평균매수가 {:>14} 현재가 {:>14} 평가액 {:>12.0} 손익 {:>+12.0} ({:+.2}%)",
+                         asset.market, asset.volume, asset.avg_buy_price, asset.price,
+                         asset.market_value, asset.unrealized_pnl, asset.unrealized_pnl_percent);
+            }
+            println!("원화 {:.0} KRW, 보유 종목 평가액 {:.0} KRW, 총 평가액 {:.0} KRW",
+                     portfolio.krw, portfolio.holdings_value, portfolio.total_equity);
+            println!("미실현 손익 {:+.0} KRW, 실현 손익 {:+.0} KRW (수수료 {:.0} KRW)",
+                     portfolio.unrealized_pnl, portfolio.realized_pnl, portfolio.fees);
+        }
+        Command::Orders { state } => {
+            let account = require_account();
+            match get_orders(&account, &state).await {
+                Ok(orders) => {
+                    for order in orders {
+                        println!("{} {} {} {} 가격 {:?} 수량 {:?} 체결 {} ({})",
+                                 order.created_at, order.market, order.side, order.ord_type,
+                                 order.price, order.volume, order.executed_volume, order.state);
+                    }
+                }
+                Err(e) => eprintln!("주문 목록을 불러올 수 없습니다: {e:?}"),
+            }
+        }
+        Command::Markets { markets } => {
+            let filter = markets.filter();
+            let all_markets = match get_all_markets().await {
+                Ok(all_markets) => all_markets,
+                Err(e) => {
+                    eprintln!("마켓 목록을 불러올 수 없습니다: {e}");
+                    std::process::exit(1);
+                }
+            };
+            let snapshots = match filter.min_trade_value_24h {
+                Some(_) => get_tickers(&filter.quote_markets(&all_markets)).await.unwrap_or_default(),
+                None => Vec::new(),
+            };
+            // 입출금 현황은 계정 키가 있을 때만 조회할 수 있습니다.
+            let suspended = match load_account() {
+                Ok(account) if filter.exclude_suspended_wallets => get_wallet_status(&account).await
+                    .map(|statuses| suspended_currencies(&statuses))
+                    .unwrap_or_default(),
+                _ => HashSet::new(),
+            };
+            let selected = filter.without_suspended(filter.select(&all_markets, &snapshots), &suspended);
+            for ticker in all_markets.iter().filter(|ticker| selected.contains(&ticker.market)) {
+                let mut flags = Vec::new();
+                if ticker.is_warned() {
+                    flags.push("유의".to_string());
+                }
+                if ticker.has_caution() {
+                    flags.push(format!("주의({})", ticker.caution_reasons().join(", ")));
+                }
+                println!("{:<12} {} / {} {}", ticker.market, ticker.korean_name, ticker.english_name, flags.join(" "));
+            }
+        }
+        Command::Tickers { markets } => {
+            let markets = if markets.is_empty() { require_all_tickers().await } else { markets };
+            match get_tickers(&markets).await {
+                Ok(snapshots) => for snapshot in snapshots {
+                    println!("{} 현재가 {} ({:+.2}%) 시가 {} 고가 {} 저가 {} 24시간 거래대금 {:.0} 52주 최고 {} ({}) 최저 {} ({})",
+                             snapshot.market, snapshot.trade_price, snapshot.signed_change_rate * 100.0,
+                             snapshot.opening_price, snapshot.high_price, snapshot.low_price, snapshot.acc_trade_price_24h,
+                             snapshot.highest_52_week_price, snapshot.highest_52_week_date,
+                             snapshot.lowest_52_week_price, snapshot.lowest_52_week_date);
+                },
+                Err(e) => eprintln!("현재가를 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Trades { market, ticks, large_trade_multiple } => {
+            match get_recent_trade_ticks(&market, ticks).await {
+                Ok(ticks) => {
+                    let flow = TradeFlow::from_ticks(&ticks, large_trade_multiple);
+                    println!("{market} 최근 {}건 매수 체결량 {} 매도 체결량 {} delta {} ({:+.3}) 대량 체결 delta {}",
+                             ticks.len(), flow.buy_volume, flow.sell_volume, flow.delta, flow.delta_ratio, flow.large_trade_delta);
+                    for trade in &flow.large_trades {
+                        let side = match trade.side {
+                            TradeSide::Bid => "매수",
+                            TradeSide::Ask => "매도",
+                        };
+                        println!("  대량 {side} 체결: {} 가격 {} 수량 {}", trade.timestamp, trade.price, trade.volume);
+                    }
+                }
+                Err(e) => eprintln!("체결 내역을 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Candles { market, unit, count } => {
+            match guaranteed_get_candle_data(&market, unit, count).await {
+                Ok(candle_data) => for candle in candle_data.iter().rev() {
+                    println!("{} 시가 {} 고가 {} 저가 {} 종가 {} 거래량 {}",
+                             candle.candle_date_time_kst, candle.opening_price, candle.high_price,
+                             candle.low_price, candle.trade_price, candle.candle_acc_trade_volume);
+                },
+                Err(e) => eprintln!("{market}의 캔들 데이터를 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Orderbook { market, depth, amount } => {
+            match get_orderbook(&market).await {
+                Ok(orderbook) => {
+                    let within = orderbook.depth_within(depth);
+                    println!("{} 매도 {} 매수 {} 중간가 {} 스프레드 {:.3}% 잔량 비율 {:+.3}",
+                             orderbook.market, orderbook.best_ask().unwrap_or(0.0), orderbook.best_bid().unwrap_or(0.0),
+                             orderbook.mid_price().unwrap_or(0.0), orderbook.spread_percent().unwrap_or(0.0), orderbook.imbalance());
+                    println!("중간가 ±{depth}% 잔량: 매수 {} {quote}, 매도 {} {quote}",
+                             within.bid_value, within.ask_value, quote = QuoteCurrency::of_market(&market).code());
+                    if let Some(amount) = amount {
+                        println!("{}", describe_buy_estimate(&orderbook, amount));
+                    }
+                }
+                Err(e) => eprintln!("호가를 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Buy { market, amount, yes } => {
+            let account = require_account();
+            // 보내기 전에 호가 잔량으로 체결 가격을 추정해 보여줍니다.
+            if let Ok(orderbook) = get_orderbook(&market).await {
+                println!("{}", describe_buy_estimate(&orderbook, amount));
+            }
+            let quote = QuoteCurrency::of_market(&market);
+            if yes || confirm(&format!("{market}을(를) {amount} {}만큼 시장가 매수합니다.", quote.code())) {
+                match buy_market_order(&account, &market, amount, None).await {
+                    Ok(()) => println!("매수 주문을 보냈습니다."),
+                    Err(e) => eprintln!("{e}"),
+                }
+            }
+        }
+        Command::Sell { market, ratio, yes } => {
+            let account = require_account();
+            if let (Ok(Some(balance)), Ok(orderbook)) = (get_balance_of(&account, &market).await, get_orderbook(&market).await) {
+                match orderbook.estimate_sell(balance * ratio / 100.0) {
+                    Some(estimate) => println!("시장가 매도 예상: 평균 체결가 {} 금액 {} {} 슬리피지 {:.3}%{}",
+                                               estimate.vwap, estimate.filled_value, QuoteCurrency::of_market(&market).code(), estimate.slippage,
+                                               if estimate.fully_filled { "" } else { " (호가 잔량 부족)" }),
+                    None => println!("호가 잔량이 없습니다."),
+                }
+            }
+            if yes || confirm(&format!("{market} 보유량의 {ratio}%를 시장가 매도합니다.")) {
+                match sell_market_order(&account, &market, ratio, None).await {
+                    Ok(()) => println!("매도 주문을 보냈습니다."),
+                    Err(e) => eprintln!("{e}"),
+                }
+            }
+        }
+        Command::Deposits { currency } => {
+            let account = require_account();
+            match get_deposits(&account, currency.as_deref()).await {
+                Ok(transfers) => print_transfers(&transfers),
+                Err(e) => eprintln!("입금 내역을 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Withdraws { currency } => {
+            let account = require_account();
+            match get_withdraws(&account, currency.as_deref()).await {
+                Ok(transfers) => print_transfers(&transfers),
+                Err(e) => eprintln!("출금 내역을 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::WithdrawChance { currency, net_type } => {
+            let account = require_account();
+            let net_type = net_type.unwrap_or_else(|| currency.clone());
+            match get_withdraw_chance(&account, &currency, &net_type).await {
+                Ok(chance) => {
+                    let limit = &chance.withdraw_limit;
+                    println!("{currency} ({net_type}) 지갑 {:?} 출금 수수료 {} 출금 가능 {} 주문중 {}",
+                             chance.currency.wallet_state, chance.currency.withdraw_fee, chance.account.balance, chance.account.locked);
+                    println!("최소 {:?} 1회 한도 {:?} 1일 한도 {:?} 남은 1일 한도 {:?} 출금 {}",
+                             limit.minimum, limit.onetime, limit.daily, limit.remaining_daily,
+                             if limit.can_withdraw { "가능" } else { "불가" });
+                }
+                Err(e) => eprintln!("출금 가능 정보를 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Withdraw { currency, amount, address, net_type, secondary_address, yes } => {
+            let account = require_account();
+            let allowlist = require_allowlist();
+            let withdrawal = CoinWithdrawal {
+                net_type: net_type.unwrap_or_else(|| currency.clone()),
+                currency,
+                amount,
+                address,
+                secondary_address,
+            };
+            if !allowlist.permits(&withdrawal) {
+                eprintln!("{} {} 주소 {}는 출금 허용 목록에 없습니다.", withdrawal.currency, withdrawal.net_type, withdrawal.address);
+                std::process::exit(1);
+            }
+            if yes || confirm(&format!("{} {}를 {} ({})로 출금합니다.", withdrawal.amount, withdrawal.currency, withdrawal.address, withdrawal.net_type)) {
+                match withdraw_coin(&account, &allowlist, &withdrawal).await {
+                    Ok(transfer) => println!("출금 요청 완료: {} ({})", transfer.uuid, transfer.state),
+                    Err(e) => eprintln!("출금 실패: {e}"),
+                }
+            }
+        }
+        Command::WithdrawKrw { amount, two_factor, yes } => {
+            let account = require_account();
+            let allowlist = require_allowlist();
+            if yes || confirm(&format!("{amount} KRW를 등록된 계좌로 출금합니다.")) {
+                match withdraw_krw(&account, &allowlist, amount, &two_factor).await {
+                    Ok(transfer) => println!("출금 요청 완료: {} ({})", transfer.uuid, transfer.state),
+                    Err(e) => eprintln!("출금 실패: {e}"),
+                }
+            }
+        }
+        Command::Wallets { suspended } => {
+            let account = require_account();
+            match get_wallet_status(&account).await {
+                Ok(statuses) => {
+                    let suspended_set = suspended_currencies(&statuses);
+                    for status in statuses.iter().filter(|status| !suspended || suspended_set.contains(&status.currency)) {
+                        println!("{:<8} {:<12} {:?} 블록 {} ({}분 전)",
+                                 status.currency, status.net_type, status.wallet_state,
+                                 status.block_state.as_deref().unwrap_or("-"),
+                                 status.block_elapsed_minutes.map_or("-".to_string(), |minutes| minutes.to_string()));
+                    }
+                }
+                Err(e) => eprintln!("입출금 현황을 불러올 수 없습니다: {e}"),
+            }
+        }
+        Command::Signals { unit, weights } => {
+            let report = scan_candles(require_all_tickers().await, unit, 200, &ScanOptions::default()).await;
+            // 매수 후보는 점수 높은 순서로 출력합니다.
+            for (candle_data, score) in rank_buy_candidates(&report.candle_datas, &weights.weights()) {
+                println!("{:<12} 매수 점수 {:.2} (다이버전스 {:.2}, RSI {:.2}, EWM {:.2}, 거래량 {:.2}) RSI {:.2} EWM {:.2} 현재가 {}",
+                         score.market, score.total, score.divergence, score.rsi, score.ewm, score.volume,
+                         candle_data.get_rsi(), candle_data.get_ewm_mean(), candle_data[0].trade_price);
+            }
+            for candle_data in &report.candle_datas {
+                if is_sell_signal(candle_data) {
+                    println!("{:<12} 매도 RSI {:.2} EWM {:.2} 현재가 {}",
+                             candle_data[0].market, candle_data.get_rsi(), candle_data.get_ewm_mean(), candle_data.get_last_price());
+                }
+            }
+            if !report.failed.is_empty() {
+                eprintln!("캔들 데이터를 받지 못한 종목: {}", report.failed.join(", "));
+            }
+        }
+    }
+}
+
+async fn run_service(executor: OrderExecutor, mode: &'static str, options: ServiceOptions) {
+    let journal = match TradeJournal::open(&options.journal) {
+        Ok(journal) => journal,
+        Err(e) => {
+            eprintln!("거래 일지 파일을 열 수 없습니다: {e}");
+            std::process::exit(1);
+        }
+    };
+    let state = Arc::new(ServiceState::new(mode, journal));
+
+    if let Some(bind) = options.http {
+        // 토큰을 따로 지정하지 않으면 실행할 때마다 새로 만들어 한 번만 출력합니다.
+        let token = std::env::var("YIPIR_CONTROL_TOKEN").unwrap_or_else(|_| {
+            let token = uuid::Uuid::new_v4().to_string();
+            println!("제어 API 토큰: {token}");
+            token
+        });
+        if let Err(e) = spawn_control_server(&bind, Arc::clone(&state), executor.clone(), options.weights.weights(), token) {
+            eprintln!("제어 API 서버를 열 수 없습니다: {e}");
+            std::process::exit(1);
+        }
+        println!("제어 API: http://{bind}");
+    }
+
+    // 고정 위험 방식의 손절 폭은 --risk-stop이 없으면 청산 규칙에서 가져옵니다.
+    let stop = options.stop_loss
+        .or(options.trailing_stop)
+        .map(StopDistance::Percent)
+        .or(options.trailing_atr.map(|multiplier| StopDistance::Atr { multiplier, period: options.sizing.atr_period as usize }));
+    let confirmations = options.confirmations.confirmations();
+    let sizing = build_sizing(&options.sizing, stop, &confirmations).await;
+
+    let config = ServiceConfig {
+        drain_timeout: Duration::from_secs(options.shutdown_timeout),
+        cancel_open_orders: options.cancel_open_orders,
+        scan: ScanOptions {
+            concurrency: options.scan_concurrency,
+            market_timeout: Duration::from_secs(options.market_timeout),
+            ..ScanOptions::default()
+        },
+        exits: ExitRules {
+            stop_loss: options.stop_loss,
+            take_profit: options.take_profit,
+            trailing_stop: match (options.trailing_stop, options.trailing_atr) {
+                (Some(percent), _) => Some(TrailingStop::Percent(percent)),
+                (None, Some(multiplier)) => Some(TrailingStop::Atr { multiplier, period: options.sizing.atr_period as usize }),
+                (None, None) if options.trailing_sar => Some(TrailingStop::ParabolicSar(SarOptions { step: options.sar_step, max_step: options.sar_max })),
+                (None, None) => None,
+            },
+            max_holding: options.max_holding.map(|minutes| Duration::from_secs(minutes * 60)),
+        },
+        risk: RiskLimits {
+            max_open_positions: options.max_positions,
+            max_market_exposure: options.max_market_exposure,
+            max_total_exposure: options.max_total_exposure,
+            daily_loss_limit: options.daily_loss_limit,
+            kill_switch_loss: options.kill_switch_loss,
+            max_orders_per_minute: options.max_orders_per_minute,
+            max_slippage: options.max_slippage,
+        },
+        sizing,
+        scoring: ScoringConfig {
+            weights: options.weights.weights(),
+            top_n: options.top_n,
+        },
+        flow: options.flow.conditions(),
+        confirmations,
+        markets: options.markets.filter(),
+    };
+    // 재시작 전에 열었던 보유 종목과 주문을 첫 스캔 전에 거래 일지와 맞춥니다.
+    let report = match reconcile(&executor, &state, options.markets.quote, !options.ignore_unknown_positions).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("거래소 상태를 거래 일지와 맞출 수 없습니다: {e}");
+            std::process::exit(1);
+        }
+    };
+    print!("{report}");
+    state.set_reconciliation(report);
+
+    let service = spawn_yipir_upbit_service(executor.clone(), Arc::clone(&state), config).await;
+
+    let signal_state = Arc::clone(&state);
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        println!("종료 신호를 받았습니다. 진행 중인 주문을 정리합니다...");
+        signal_state.request_shutdown();
+    });
+
+    let summary = match service.await {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("서비스가 비정상 종료되었습니다: {e}");
+            if let Err(e) = state.journal.flush() {
+                eprintln!("거래 일지를 저장할 수 없습니다: {e}");
+            }
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = state.journal.flush() {
+        eprintln!("거래 일지를 저장할 수 없습니다: {e}");
+    }
+    println!("{summary}");
+}
+
+// 켈리 기준 통계를 구하는 백테스트의 window
+const KELLY_BACKTEST_WINDOW: usize = 100;
+
+/// # 매수 금액 결정 방식 만들기
+/// kelly 방식은 kelly_market의 1분봉으로 기본 비율 백테스트를 돌려 승률과 손익비를 구하며, 구할 수 없으면 fixed 방식으로 대신합니다.
+async fn build_sizing(options: &SizingOptions, stop: Option<StopDistance>, confirmations: &Confirmations) -> PositionSizing {
+    match options.sizing {
+        SizingMethod::Fixed => PositionSizing::FixedFraction(options.fraction),
+        SizingMethod::Volatility => PositionSizing::VolatilityTarget {
+            target: options.target_volatility,
+            measure: match options.volatility_measure {
+                VolatilitySource::Std => VolatilityMeasure::Std,
+                VolatilitySource::Atr => VolatilityMeasure::Atr(options.atr_period as usize),
+            },
+        },
+        SizingMethod::FixedRisk => match options.risk_stop.map(StopDistance::Percent).or(stop) {
+            Some(stop) => PositionSizing::FixedRisk { risk: options.risk_per_trade, stop },
+            None => {
+                eprintln!("fixed-risk 방식에는 --risk-stop, --stop-loss, --trailing-stop, --trailing-atr 중 하나가 필요합니다.");
+                std::process::exit(1);
+            }
+        },
+        SizingMethod::Kelly => {
+            let candle_data = match guaranteed_get_candle_data(&options.kelly_market, CandleUnit::Min1, 200).await {
+                Ok(candle_data) => candle_data,
+                Err(e) => {
+                    eprintln!("{}의 캔들 데이터를 불러올 수 없습니다: {e}", options.kelly_market);
+                    std::process::exit(1);
+                }
+            };
+            // 새로 상장된 마켓처럼 캔들이 백테스트 window보다 적으면 백테스트를 돌릴 수 없습니다.
+            if candle_data.len() < KELLY_BACKTEST_WINDOW {
+                eprintln!("{}의 1분봉이 {}개뿐이라 켈리 비율을 구할 수 없습니다. 잔고의 {} 비율로 매수합니다. (--fraction)",
+                          options.kelly_market, candle_data.len(), options.fraction);
+                return PositionSizing::FixedFraction(options.fraction);
+            }
+            let report = run_backtest(&candle_data, KELLY_BACKTEST_WINDOW, 1_000_000.0, &PositionSizing::default(), confirmations);
+            // 백테스트 거래가 부족하면 켈리 비율 대신 fixed 방식의 비율로 매수합니다.
+            let Some(stats) = KellyStats::from_report(&report) else {
+                eprintln!("{}의 백테스트 거래가 부족하여 켈리 비율을 구할 수 없습니다. 잔고의 {} 비율로 매수합니다. (--fraction)",
+                          options.kelly_market, options.fraction);
+                return PositionSizing::FixedFraction(options.fraction);
+            };
+            println!("켈리 기준: 승률 {:.1}%, 손익비 {:.2}, 켈리 비율 {:.1}%",
+                     stats.win_rate * 100.0, stats.payoff_ratio, stats.kelly() * 100.0);
+            PositionSizing::Kelly { fraction: options.kelly_fraction, stats }
+        }
+    }
+}
+
+fn require_account() -> Arc<UpbitAccount> {
+    match load_account() {
+        Ok(account) => account,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// API 키를 점검하여 만료 시각과 권한을 출력합니다. 키를 확인할 수 없으면 종료합니다.
+async fn require_key_report(account: &UpbitAccount) -> KeyReport {
+    match inspect_api_key(account).await {
+        Ok(report) => {
+            let remaining = report.remaining().map_or("알 수 없음".to_string(), |remaining| format!("{}일 남음", remaining.as_secs() / 86_400));
+            let permissions = report.permissions.iter().map(|permission| permission.to_string()).collect::<Vec<String>>();
+            println!("API 키 {} 만료 {} ({remaining}), 권한: {}", report.access_key, report.expire_at, permissions.join(", "));
+            report
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// 원화 마켓 목록을 불러오며, 불러올 수 없으면 종료합니다.
+async fn require_all_tickers() -> Vec<String> {
+    match get_all_tickers().await {
+        Ok(tickers) => tickers,
+        Err(e) => {
+            eprintln!("마켓 목록을 불러올 수 없습니다: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn require_allowlist() -> WithdrawalAllowlist {
+    match WithdrawalAllowlist::from_env() {
+        Ok(allowlist) => allowlist,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_transfers(transfers: &[Transfer]) {
+    for transfer in transfers {
+        println!("{} {:<8} {:>20} 수수료 {:>12} {:<10} {}",
+                 transfer.created_at, transfer.currency, transfer.amount, transfer.fee, transfer.state,
+                 transfer.txid.as_deref().unwrap_or(""));
+    }
+}
+
+// 표준 입력으로 y를 받은 경우에만 true를 반환합니다.
+fn confirm(message: &str) -> bool {
+    print!("{message} 계속하시겠습니까? [y/N] ");
+    std::io::stdout().flush().unwrap();
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+// 호가 잔량으로 추정한 시장가 매수 체결 결과를 설명합니다.
+fn describe_buy_estimate(orderbook: &Orderbook, amount: f64) -> String {
+    let quote = QuoteCurrency::of_market(&orderbook.market).code();
+    match orderbook.estimate_buy(amount) {
+        Some(estimate) if estimate.fully_filled => format!("{amount} {quote} 시장가 매수 예상: 평균 체결가 {} 수량 {} 슬리피지 {:.3}%",
+                                                           estimate.vwap, estimate.volume, estimate.slippage),
+        Some(estimate) => format!("호가 잔량으로 {} {quote}까지만 체결할 수 있습니다. (평균 체결가 {}, 슬리피지 {:.3}%)",
+                                  estimate.filled_value, estimate.vwap, estimate.slippage),
+        None => "호가 잔량이 없습니다.".to_string(),
+    }
+}
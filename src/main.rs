@@ -1,8 +1,10 @@
-use crate::upbit::spawn_yipir_upbit_service;
+use clap::Parser;
+use crate::cli::{Cli, execute};
 
+mod cli;
 mod upbit;
 
 #[tokio::main]
 async fn main() {
-    let _ = spawn_yipir_upbit_service().await.await;
-}
\ No newline at end of file
+    execute(Cli::parse()).await;
+}
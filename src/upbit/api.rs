@@ -1,14 +1,26 @@
 use reqwest::{Client, Response, RequestBuilder};
-use serde_json::{Value};
 use serde::de::DeserializeOwned;
 use crate::upbit::{UpbitAccount, response::*};
+use std::future::Future;
+use crate::upbit::circuit_breaker::{breaker_of, CircuitState};
+use crate::upbit::rate_limit::{limiter_of, RateGroup};
+use crate::upbit::wallet::{CoinWithdrawal, WithdrawalAllowlist};
 use tokio::{time};
 use tokio::time::Duration;
 
+// guaranteed_* 함수의 재시도 간격과 최대 시도 횟수, 그 전에 회로 차단기가 열리면 바로 포기합니다.
+const RETRY_INTERVAL: Duration = Duration::from_millis(200);
+const MAX_ATTEMPTS: u32 = 10;
+// 현재가 조회 한 번에 담을 종목 수
+const TICKER_BATCH_SIZE: usize = 100;
+// 체결 조회 한 번에 받을 수 있는 최대 개수
+const TRADE_TICKS_PAGE_SIZE: usize = 500;
+// 주문 목록 조회 한 번에 받을 수 있는 최대 개수
+pub const ORDERS_PAGE_LIMIT: usize = 100;
+
 // UPBit API 측과 상관 없는 에러
 #[derive(Debug)]
 pub enum InternalRequestError {
-    ErrorWhileSend,
     WrongMethod,
 }
 
@@ -17,21 +29,42 @@ pub enum InternalRequestError {
 pub enum UpbitResponseError {
     MismatchedResponseType,
     TooManyApiCall,
+    // 요청을 보내지 못한 경우
+    Unreachable,
+    // UPBit이 요청을 거절한 경우 (에러 이름, 메시지)
+    Rejected(String, String),
+    // 거래소 장애로 회로 차단기가 요청을 막고 있는 경우
+    CircuitOpen,
+}
+
+impl UpbitResponseError {
+    // 잘못된 마켓 코드처럼 거래소가 거절한 요청은 다시 보내도 같은 결과이므로 재시도하지 않습니다.
+    fn is_retryable(&self) -> bool {
+        !matches!(self, UpbitResponseError::Rejected(_, _) | UpbitResponseError::CircuitOpen)
+    }
+}
+
+impl std::fmt::Display for UpbitResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpbitResponseError::MismatchedResponseType => f.write_str("응답 형식이 일치하지 않습니다."),
+            UpbitResponseError::TooManyApiCall => f.write_str("API 호출 횟수 제한을 초과했습니다."),
+            UpbitResponseError::Unreachable => f.write_str("UPBit에 연결할 수 없습니다."),
+            UpbitResponseError::Rejected(name, message) => write!(f, "{name}: {message}"),
+            UpbitResponseError::CircuitOpen => f.write_str("거래소 장애로 요청이 차단되어 있습니다."),
+        }
+    }
 }
 
 enum RequestMethod {
     Get,
     Post,
+    Delete,
 }
 
+#[derive(Default)]
 struct UpbitRequestBuilder {}
 
-impl Default for UpbitRequestBuilder {
-    fn default() -> Self {
-        UpbitRequestBuilder {}
-    }
-}
-
 impl UpbitRequestBuilder {
     fn get(self, url: String) -> UpbitRequestConfig {
         UpbitRequestConfig {
@@ -48,6 +81,14 @@ impl UpbitRequestBuilder {
             parameters: std::collections::HashMap::new(),
         }
     }
+
+    fn delete(self, url: String) -> UpbitRequestConfig {
+        UpbitRequestConfig {
+            url,
+            method: RequestMethod::Delete,
+            parameters: std::collections::HashMap::new(),
+        }
+    }
 }
 
 struct UpbitRequestConfig {
@@ -66,7 +107,7 @@ impl UpbitRequestConfig {
     fn public(mut self) -> Result<UpbitRequest, InternalRequestError> {
         if !self.parameters.is_empty() {
             let (query_string, _) = generate_request_body(&self.parameters);
-            self.url.push_str("?");
+            self.url.push('?');
             self.url.push_str(&query_string);
         }
 
@@ -78,7 +119,8 @@ impl UpbitRequestConfig {
         let upbit_request = UpbitRequest {
             reqwest_builder: with_method
                 .header("Accept", "application/json")
-                .header("Content-Type", "application/json")
+                .header("Content-Type", "application/json"),
+            rate_group: RateGroup::Quotation,
         };
 
         Ok(upbit_request)
@@ -92,44 +134,55 @@ impl UpbitRequestConfig {
 
         let (query_string, json_string) = generate_request_body(&self.parameters);
         if !self.parameters.is_empty() {
-            self.url.push_str("?");
+            self.url.push('?');
             self.url.push_str(&query_string);
         }
 
         let uuid = uuid::Uuid::new_v4().to_string();
-        let key: Hmac<Sha256> = Hmac::new_from_slice(upbit_account.secret_key.as_bytes()).unwrap();
+        let key: Hmac<Sha256> = Hmac::new_from_slice(upbit_account.secret_key.expose().as_bytes()).unwrap();
         let mut claims: BTreeMap<&str, &str> = BTreeMap::new();
-        claims.insert("access_key", &upbit_account.access_key);
+        claims.insert("access_key", upbit_account.access_key.expose());
         claims.insert("nonce", &uuid);
 
+        // 파라미터가 있는 요청은 GET, POST 모두 쿼리 해시를 포함해야 합니다.
+        let mut buf = [0u8; 1024];
+        if !self.parameters.is_empty() {
+            let query_hash = Sha512::digest(&query_string);
+            let hash_string = base16ct::lower::encode_str(&query_hash, &mut buf).expect("JWT 생성을 위한 버퍼 크기가 너무 작습니다.");
+            claims.insert("query_hash", hash_string);
+            claims.insert("query_hash_alg", "SHA512");
+        }
+        let token_str = claims.sign_with_key(&key).unwrap();
 
+        let rate_group = match self.method {
+            RequestMethod::Get => RateGroup::Exchange,
+            RequestMethod::Post | RequestMethod::Delete => RateGroup::Order,
+        };
         let with_method = match self.method {
             RequestMethod::Get => {
-                let token_str = claims.sign_with_key(&key).unwrap();
                 Client::default()
                     .get(self.url)
                     .bearer_auth(&token_str)
             }
             RequestMethod::Post => {
-                let mut buf = [0u8; 1024];
-                let query_hash = Sha512::digest(&query_string);
-                let hash_string = base16ct::lower::encode_str(&query_hash, &mut buf).expect("JWT 생성을 위한 버퍼 크기가 너무 작습니다.");
-                claims.insert("query_hash", &hash_string);
-                claims.insert("query_hash_alg", "SHA512");
-                let token_str = claims.sign_with_key(&key).unwrap();
                 Client::default()
                     .post(self.url)
                     .json(&json_string)
                     .bearer_auth(&token_str)
             }
+            RequestMethod::Delete => {
+                Client::default()
+                    .delete(self.url)
+                    .bearer_auth(&token_str)
+            }
         };
 
 
         let upbit_request = UpbitRequest {
             reqwest_builder: with_method
                 .header("Accept", "application/json")
-                .header("Content-Type", "application/json")
-
+                .header("Content-Type", "application/json"),
+            rate_group,
         };
 
         Ok(upbit_request)
@@ -138,26 +191,54 @@ impl UpbitRequestConfig {
 
 struct UpbitRequest {
     reqwest_builder: RequestBuilder,
+    rate_group: RateGroup,
 }
 
 impl UpbitRequest {
-    async fn execute(self) -> Result<UpbitResponse, InternalRequestError> {
-        return match self.reqwest_builder.send().await {
-            Ok(response) => Ok(UpbitResponse { reqwest_response: response }),
-            Err(_) => Err(InternalRequestError::ErrorWhileSend),
+    // 요청 그룹의 회로 차단기가 열려 있으면 닫힐 때까지 기다린 뒤 보냅니다.
+    async fn execute(self) -> Result<UpbitResponse, UpbitResponseError> {
+        let breaker = breaker_of(self.rate_group);
+        breaker.ready().await;
+        limiter_of(self.rate_group).acquire().await;
+        match self.reqwest_builder.send().await {
+            Ok(response) => Ok(UpbitResponse { reqwest_response: response, rate_group: self.rate_group }),
+            Err(_) => {
+                breaker.record_failure();
+                Err(UpbitResponseError::Unreachable)
+            }
         }
     }
 }
 
 struct UpbitResponse {
     reqwest_response: Response,
+    rate_group: RateGroup,
 }
 
 impl UpbitResponse {
+    // 요청 제한(429)과 서버 오류(5xx)만 장애로 보고, 그 밖의 응답은 거래소가 정상이라는 뜻으로 봅니다.
     async fn response<T>(self) -> Result<T, UpbitResponseError>
         where
     T: DeserializeOwned {
-        return match self.reqwest_response.json::<T>().await {
+        let status = self.reqwest_response.status();
+        let breaker = breaker_of(self.rate_group);
+        if status.as_u16() == 429 || status.is_server_error() {
+            breaker.record_failure();
+        } else {
+            breaker.record_success();
+        }
+
+        if status.as_u16() == 429 {
+            return Err(UpbitResponseError::TooManyApiCall);
+        }
+        if !status.is_success() {
+            return match self.reqwest_response.json::<UpbitErrorBody>().await {
+                Ok(body) => Err(UpbitResponseError::Rejected(body.error.name, body.error.message)),
+                Err(_) => Err(UpbitResponseError::MismatchedResponseType),
+            };
+        }
+
+        match self.reqwest_response.json::<T>().await {
             Ok(deserialized) => Ok(deserialized),
             Err(_) => Err(UpbitResponseError::MismatchedResponseType),
         }
@@ -181,120 +262,306 @@ fn generate_request_body(parameters: &std::collections::HashMap<String, String>)
     query_string.pop().expect("#");
     json_string.pop().expect("#");
     json_string.pop().expect("#");
-    json_string.push_str("}");
+    json_string.push('}');
 
     (query_string, json_string)
 }
 
-pub async fn get_all_balances(account: &UpbitAccount) -> Vec<Balance> {
-    let mut interval = tokio::time::interval(Duration::from_millis(200));
-    let mut count: u8 = 0;
+pub async fn get_all_balances(account: &UpbitAccount) -> Result<Vec<Balance>, UpbitResponseError> {
+    retry_transient_errors(RateGroup::Exchange, || get_balances(account)).await
+}
+
+pub async fn get_balances(account: &UpbitAccount) -> Result<Vec<Balance>, UpbitResponseError> {
+    let request = || UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/accounts".to_string())
+        .private(account).unwrap();
+    match request().execute().await?.response::<Vec<Balance>>().await {
+        Ok(vec) => Ok(vec),
+        // 보유 자산이 하나면 배열이 아닌 객체로 올 수 있습니다.
+        Err(UpbitResponseError::MismatchedResponseType) => request()
+            .execute().await?
+            .response::<Balance>().await
+            .map(|one| vec![one]),
+        Err(e) => Err(e),
+    }
+}
+
+/// # 일시적인 오류 재시도
+/// 연결 실패, 429처럼 일시적인 오류는 잠시 쉬었다가 MAX_ATTEMPTS번까지 다시 요청합니다.
+/// 거래소가 거절한 요청은 바로 에러를 반환하고, group의 회로 차단기가 열려 있으면 차단이 풀리기를 기다리지 않고 CircuitOpen을 반환합니다.
+async fn retry_transient_errors<T, F, Fut>(group: RateGroup, mut request: F) -> Result<T, UpbitResponseError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, UpbitResponseError>> {
+    let mut attempts = 0;
     loop {
-        if count > 100 { panic!("잔고 정보를 불러올 수 없습니다.") }
-        count += 1;
-        interval.tick().await;
-        match UpbitRequestBuilder::default()
-            .get("https://api.upbit.com/v1/accounts".to_string())
-            .private(account).unwrap()
-            .execute().await.unwrap()
-            .response::<Vec<Balance>>().await {
-            Ok(vec) => return vec,
-            Err(_) => {
-                match UpbitRequestBuilder::default()
-                    .get("https://api.upbit.com/v1/accounts".to_string())
-                    .private(account).unwrap()
-                    .execute().await.unwrap()
-                    .response::<Balance>().await {
-                    Ok(one) => return vec![one],
-                    Err(_) => continue
-                }
-            }
+        if breaker_of(group).state() == CircuitState::Open {
+            return Err(UpbitResponseError::CircuitOpen);
+        }
+        attempts += 1;
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(e) if !e.is_retryable() || attempts >= MAX_ATTEMPTS => return Err(e),
+            Err(_) => time::sleep(RETRY_INTERVAL).await,
         }
     }
 }
 
-pub async fn get_balance_of(account: &UpbitAccount, ticker: &str) -> Option<f64> {
-    // KRW-XXX의 꼴을 XXX로 만들고, KRW일 경우에는 유지
-    let search_for = if ticker == "KRW" {
-        "KRW"
-    } else {
-        ticker.split("-").collect::<Vec<&str>>()[1]
-    };
+pub async fn get_balance_of(account: &UpbitAccount, ticker: &str) -> Result<Option<f64>, UpbitResponseError> {
+    // KRW-XXX, BTC-XXX의 꼴을 XXX로 만들고, KRW, BTC 같은 통화 코드는 유지
+    let search_for = ticker.split_once('-').map_or(ticker, |(_, base)| base);
 
-    let balances = get_all_balances(account).await
+    let balances = get_all_balances(account).await?
         .iter()
         .filter(|balance| balance.currency == search_for)
         .map(|balance| balance.balance)
         .collect::<Vec<f64>>();
 
-    if balances.is_empty() {
-        None
-    } else {
-        Some(balances[0])
+    Ok(balances.first().copied())
+}
+
+/// # 현재가 스냅샷 조회
+/// 한 번의 요청에 여러 종목을 담아 조회하며, 종목이 많으면 TICKER_BATCH_SIZE개씩 나누어 요청합니다.
+pub async fn get_tickers(markets: &[String]) -> Result<Vec<TickerSnapshot>, UpbitResponseError> {
+    let mut snapshots = Vec::with_capacity(markets.len());
+    for chunk in markets.chunks(TICKER_BATCH_SIZE) {
+        let mut chunk_snapshots = UpbitRequestBuilder::default()
+            .get(format!("https://api.upbit.com/v1/ticker?markets={}", chunk.join(",")))
+            .public().unwrap()
+            .execute().await?
+            .response::<Vec<TickerSnapshot>>().await?;
+        snapshots.append(&mut chunk_snapshots);
     }
+    Ok(snapshots)
+}
+
+pub async fn guaranteed_get_tickers(markets: &[String]) -> Result<Vec<TickerSnapshot>, UpbitResponseError> {
+    retry_transient_errors(RateGroup::Quotation, || get_tickers(markets)).await
 }
 
-#[allow(dead_code)]
 pub async fn get_price_of(ticker: &str) -> Result<f64, UpbitResponseError> {
-    match UpbitRequestBuilder::default()
-        .get(format!("https://api.upbit.com/v1/ticker?markets={ticker}"))
-        .public().unwrap()
-        .execute().await.unwrap()
-        .response::<Vec<Value>>().await {
-        Ok(jsons) => Ok(jsons[0]["trade_price"].as_f64().unwrap()),
-        Err(_) => Err(UpbitResponseError::TooManyApiCall),
-    }
+    get_tickers(&[ticker.to_string()])
+        .await?
+        .first()
+        .map(|snapshot| snapshot.trade_price)
+        .ok_or(UpbitResponseError::MismatchedResponseType)
 }
 
-#[allow(dead_code)]
-pub async fn guaranteed_get_price_of(ticker: &str) -> f64 {
-    let mut interval = tokio::time::interval(Duration::from_millis(200));
-    loop {
-        interval.tick().await;
-        match get_price_of(ticker).await {
-            Ok(price) => return price,
-            Err(_) => continue,
-        }
-    }
+pub async fn guaranteed_get_price_of(ticker: &str) -> Result<f64, UpbitResponseError> {
+    retry_transient_errors(RateGroup::Quotation, || get_price_of(ticker)).await
 }
 
-pub async fn buy_market_order(account: &UpbitAccount, ticker: &str, budget: f64) {
+/// # 시장가 매수
+/// identifier를 지정하면 같은 identifier의 주문은 UPBit에서 한 번만 접수됩니다.
+pub async fn buy_market_order(account: &UpbitAccount, ticker: &str, budget: f64, identifier: Option<&str>) -> Result<(), String> {
     let budget_string = budget.to_string();
-    UpbitRequestBuilder::default()
+    let mut request = UpbitRequestBuilder::default()
         .post("https://api.upbit.com/v1/orders".to_string())
         .add_parameter("market", ticker)
         .add_parameter("side", "bid")
         .add_parameter("price", &budget_string)
-        .add_parameter("ord_type", "price")
+        .add_parameter("ord_type", "price");
+    if let Some(identifier) = identifier {
+        request = request.add_parameter("identifier", identifier);
+    }
+
+    request
+        .private(account).unwrap()
+        .execute().await.map_err(|e| e.to_string())?
+        .response::<Order>().await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// # 주문 조회
+/// 주문 시 지정한 identifier로 주문 상태를 조회합니다.
+pub async fn get_order_by_identifier(account: &UpbitAccount, identifier: &str) -> Result<Order, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/order".to_string())
+        .add_parameter("identifier", identifier)
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Order>().await
+}
+
+/// # 주문 목록 페이지
+/// state 상태의 주문을 최신순으로 page(1부터)번째 페이지에서 최대 ORDERS_PAGE_LIMIT건 불러옵니다.
+pub async fn get_orders_page(account: &UpbitAccount, state: &str, page: u32) -> Result<Vec<Order>, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/orders".to_string())
+        .add_parameter("state", state)
+        .add_parameter("page", &page.to_string())
+        .add_parameter("limit", &ORDERS_PAGE_LIMIT.to_string())
+        .add_parameter("order_by", "desc")
         .private(account).unwrap()
-        .execute().await.unwrap()
-        .response::<Value>().await.unwrap();
+        .execute().await?
+        .response::<Vec<Order>>().await
 }
 
-pub async fn sell_market_order(account: &UpbitAccount, ticker: &str, ratio: f64) -> Result<(), String> {
-    if ratio < 0.0 || ratio > 100.0 {
+/// # 주문 목록 조회
+/// state는 wait, watch, done, cancel 중 하나입니다.
+pub async fn get_orders(account: &UpbitAccount, state: &str) -> Result<Vec<Order>, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/orders".to_string())
+        .add_parameter("state", state)
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Vec<Order>>().await
+}
+
+/// # 주문 취소
+/// uuid에 해당하는 미체결 주문을 취소합니다.
+pub async fn cancel_order(account: &UpbitAccount, uuid: &str) -> Result<Order, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .delete("https://api.upbit.com/v1/order".to_string())
+        .add_parameter("uuid", uuid)
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Order>().await
+}
+
+pub async fn sell_market_order(account: &UpbitAccount, ticker: &str, ratio: f64, identifier: Option<&str>) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&ratio) {
         return Err("판매 비율이 잘못되었습니다.".to_string())
     }
 
-    if let Some(balance) = get_balance_of(account, ticker).await {
+    if let Some(balance) = get_balance_of(account, ticker).await.map_err(|e| e.to_string())? {
         let to_sell = (balance * ratio/100.0).to_string();
 
-        UpbitRequestBuilder::default()
+        let mut request = UpbitRequestBuilder::default()
             .post("https://api.upbit.com/v1/orders".to_string())
             .add_parameter("market", ticker)
             .add_parameter("side", "ask")
             .add_parameter("volume", &to_sell)
-            .add_parameter("ord_type", "market")
-            .private(account).unwrap()
-            .execute().await.unwrap()
-            .response::<Value>().await.unwrap();
+            .add_parameter("ord_type", "market");
+        if let Some(identifier) = identifier {
+            request = request.add_parameter("identifier", identifier);
+        }
 
-        Ok(())
+        request
+            .private(account).unwrap()
+            .execute().await.map_err(|e| e.to_string())?
+            .response::<Order>().await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
     } else {
         Err("판매할 보유량이 없습니다.".to_string())
     }
 }
 
+/// # 입금 내역 조회
+/// currency를 지정하지 않으면 모든 통화의 최근 입금 내역을 조회합니다.
+pub async fn get_deposits(account: &UpbitAccount, currency: Option<&str>) -> Result<Vec<Transfer>, UpbitResponseError> {
+    get_transfers(account, "https://api.upbit.com/v1/deposits", currency).await
+}
+
+/// # 출금 내역 조회
+/// currency를 지정하지 않으면 모든 통화의 최근 출금 내역을 조회합니다.
+pub async fn get_withdraws(account: &UpbitAccount, currency: Option<&str>) -> Result<Vec<Transfer>, UpbitResponseError> {
+    get_transfers(account, "https://api.upbit.com/v1/withdraws", currency).await
+}
+
+async fn get_transfers(account: &UpbitAccount, url: &str, currency: Option<&str>) -> Result<Vec<Transfer>, UpbitResponseError> {
+    let mut request = UpbitRequestBuilder::default()
+        .get(url.to_string())
+        .add_parameter("limit", "100");
+    if let Some(currency) = currency {
+        request = request.add_parameter("currency", currency);
+    }
+    request
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Vec<Transfer>>().await
+}
+
+/// # 출금 가능 정보 조회
+/// 출금 수수료, 지갑 상태, 출금 한도를 조회합니다.
+pub async fn get_withdraw_chance(account: &UpbitAccount, currency: &str, net_type: &str) -> Result<WithdrawChance, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/withdraws/chance".to_string())
+        .add_parameter("currency", currency)
+        .add_parameter("net_type", net_type)
+        .private(account).unwrap()
+        .execute().await?
+        .response::<WithdrawChance>().await
+}
+
+/// # 코인 출금
+/// 출금 허용 목록에 없는 주소로는 요청을 보내지 않습니다.
+pub async fn withdraw_coin(account: &UpbitAccount, allowlist: &WithdrawalAllowlist, withdrawal: &CoinWithdrawal) -> Result<Transfer, String> {
+    if !allowlist.permits(withdrawal) {
+        return Err(format!("{} {} 주소 {}는 출금 허용 목록에 없습니다.", withdrawal.currency, withdrawal.net_type, withdrawal.address));
+    }
+
+    let amount_string = withdrawal.amount.to_string();
+    let mut request = UpbitRequestBuilder::default()
+        .post("https://api.upbit.com/v1/withdraws/coin".to_string())
+        .add_parameter("currency", &withdrawal.currency)
+        .add_parameter("net_type", &withdrawal.net_type)
+        .add_parameter("amount", &amount_string)
+        .add_parameter("address", &withdrawal.address);
+    if let Some(secondary_address) = &withdrawal.secondary_address {
+        request = request.add_parameter("secondary_address", secondary_address);
+    }
+
+    request
+        .private(account).unwrap()
+        .execute().await.map_err(|e| e.to_string())?
+        .response::<Transfer>().await
+        .map_err(|e| e.to_string())
+}
+
+/// # 원화 출금
+/// 출금 허용 목록의 allow_krw가 true일 때만 등록된 출금 계좌로 보냅니다.
+/// two_factor_type은 kakao, naver, hana 중 하나입니다.
+pub async fn withdraw_krw(account: &UpbitAccount, allowlist: &WithdrawalAllowlist, amount: f64, two_factor_type: &str) -> Result<Transfer, String> {
+    if !allowlist.allow_krw {
+        return Err("출금 허용 목록에서 원화 출금을 허용하지 않았습니다.".to_string());
+    }
+
+    let amount_string = amount.to_string();
+    UpbitRequestBuilder::default()
+        .post("https://api.upbit.com/v1/withdraws/krw".to_string())
+        .add_parameter("amount", &amount_string)
+        .add_parameter("two_factor_type", two_factor_type)
+        .private(account).unwrap()
+        .execute().await.map_err(|e| e.to_string())?
+        .response::<Transfer>().await
+        .map_err(|e| e.to_string())
+}
+
+/// # 출금 취소
+/// uuid에 해당하는 코인 출금 요청을 취소합니다.
+pub async fn cancel_withdraw(account: &UpbitAccount, uuid: &str) -> Result<Transfer, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .delete("https://api.upbit.com/v1/withdraws/coin".to_string())
+        .add_parameter("uuid", uuid)
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Transfer>().await
+}
+
+/// # API 키 목록 조회
+/// 계정에 발급된 API 키와 만료 시각을 조회합니다.
+pub async fn get_api_keys(account: &UpbitAccount) -> Result<Vec<ApiKey>, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/api_keys".to_string())
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Vec<ApiKey>>().await
+}
+
+/// # 입출금 현황 조회
+/// 통화와 네트워크별 지갑 상태와 블록 동기화 상태를 조회합니다.
+pub async fn get_wallet_status(account: &UpbitAccount) -> Result<Vec<WalletStatus>, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/status/wallet".to_string())
+        .private(account).unwrap()
+        .execute().await?
+        .response::<Vec<WalletStatus>>().await
+}
+
+#[derive(Debug, Clone, Copy)]
 pub enum CandleUnit {
     #[allow(unused)]
     Min1,
@@ -312,6 +579,23 @@ pub enum CandleUnit {
     Hour4,
 }
 
+impl std::str::FromStr for CandleUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(CandleUnit::Min1),
+            "3m" => Ok(CandleUnit::Min3),
+            "5m" => Ok(CandleUnit::Min5),
+            "10m" => Ok(CandleUnit::Min10),
+            "30m" => Ok(CandleUnit::Min30),
+            "1h" => Ok(CandleUnit::Hour1),
+            "4h" => Ok(CandleUnit::Hour4),
+            _ => Err(format!("지원하지 않는 캔들 단위입니다: {s} (1m, 3m, 5m, 10m, 30m, 1h, 4h)")),
+        }
+    }
+}
+
 pub async fn get_candle_data(ticker: &str, unit: &CandleUnit, count: u8) -> Result<Vec<CandleData>, UpbitResponseError> {
     let interval_url = match unit {
         CandleUnit::Min1 => "minutes/1",
@@ -326,33 +610,123 @@ pub async fn get_candle_data(ticker: &str, unit: &CandleUnit, count: u8) -> Resu
     UpbitRequestBuilder::default()
         .get(format!("https://api.upbit.com/v1/candles/{interval_url}?market={ticker}&count={count}"))
         .public().unwrap()
-        .execute().await.unwrap()
-        .response::<Vec<CandleData>>().await.map_err(|_| UpbitResponseError::TooManyApiCall)
+        .execute().await?
+        .response::<Vec<CandleData>>().await
 }
 
-pub async fn guaranteed_get_candle_data(ticker: &str, unit: CandleUnit, count: u8) -> Vec<CandleData> {
-    let mut interval = time::interval(Duration::from_millis(100));
-    loop {
-        interval.tick().await;
-        match get_candle_data(ticker, &unit, count).await {
-            Ok(datas) => return datas,
-            Err(_) => continue,
-        }
-    }
+pub async fn guaranteed_get_candle_data(ticker: &str, unit: CandleUnit, count: u8) -> Result<Vec<CandleData>, UpbitResponseError> {
+    retry_transient_errors(RateGroup::Quotation, || get_candle_data(ticker, &unit, count)).await
 }
 
-pub async fn get_all_tickers() -> Vec<String> {
-    let response = UpbitRequestBuilder::default()
-        .get("https://api.upbit.com/v1/market/all".to_string())
+/// # 호가 조회
+pub async fn get_orderbook(market: &str) -> Result<Orderbook, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get(format!("https://api.upbit.com/v1/orderbook?markets={market}"))
         .public().unwrap()
-        .execute().await.unwrap()
-        .response::<Vec<Ticker>>().await.unwrap();
+        .execute().await?
+        .response::<Vec<Orderbook>>().await?
+        .into_iter()
+        .next()
+        .ok_or(UpbitResponseError::MismatchedResponseType)
+}
+
+/// # 최근 체결 조회
+/// 최신 체결부터 count개(최대 500)를 반환합니다. cursor에 sequential_id를 주면 그 체결 이전부터 조회합니다.
+pub async fn get_trade_ticks(market: &str, count: u16, cursor: Option<i64>) -> Result<Vec<TradeTick>, UpbitResponseError> {
+    let mut url = format!("https://api.upbit.com/v1/trades/ticks?market={market}&count={count}");
+    if let Some(cursor) = cursor {
+        url.push_str(&format!("&cursor={cursor}"));
+    }
+    UpbitRequestBuilder::default()
+        .get(url)
+        .public().unwrap()
+        .execute().await?
+        .response::<Vec<TradeTick>>().await
+}
 
-    let only_krws = response
+/// # 최근 체결 여러 페이지 조회
+/// cursor로 페이지를 넘기며 최신 체결부터 total개까지 모읍니다. 체결이 더 없으면 그때까지 받은 만큼 반환합니다.
+pub async fn get_recent_trade_ticks(market: &str, total: usize) -> Result<Vec<TradeTick>, UpbitResponseError> {
+    let mut ticks: Vec<TradeTick> = Vec::with_capacity(total);
+    while ticks.len() < total {
+        let count = (total - ticks.len()).min(TRADE_TICKS_PAGE_SIZE) as u16;
+        let cursor = ticks.last().map(|tick| tick.sequential_id);
+        let mut page = get_trade_ticks(market, count, cursor).await?;
+        let exhausted = page.len() < count as usize;
+        ticks.append(&mut page);
+        if exhausted {
+            break;
+        }
+    }
+    Ok(ticks)
+}
+
+pub async fn get_all_tickers() -> Result<Vec<String>, UpbitResponseError> {
+    Ok(get_all_markets()
+        .await?
         .into_iter()
-        .filter(|ticker| ticker.market.contains("KRW"))
+        .filter(|ticker| ticker.quote() == "KRW")
         .map(|ticker| ticker.market)
-        .collect::<Vec<String>>();
+        .collect::<Vec<String>>())
+}
+
+/// # 전체 마켓 정보 조회
+/// 유의/주의 종목 지정 여부를 함께 조회합니다.
+pub async fn get_all_markets() -> Result<Vec<Ticker>, UpbitResponseError> {
+    retry_transient_errors(RateGroup::Quotation, get_markets).await
+}
+
+async fn get_markets() -> Result<Vec<Ticker>, UpbitResponseError> {
+    UpbitRequestBuilder::default()
+        .get("https://api.upbit.com/v1/market/all?isDetails=true".to_string())
+        .public().unwrap()
+        .execute().await?
+        .response::<Vec<Ticker>>().await
+}
 
-    only_krws
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn rejected_request_is_not_retried() {
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = retry_transient_errors(RateGroup::Exchange, || async {
+            attempts.set(attempts.get() + 1);
+            Err(UpbitResponseError::Rejected("not_found_market".to_string(), "Code not found".to_string()))
+        }).await;
+        assert!(matches!(result, Err(UpbitResponseError::Rejected(_, _))));
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried_until_success() {
+        let attempts = Cell::new(0);
+        let result = retry_transient_errors(RateGroup::Exchange, || async {
+            attempts.set(attempts.get() + 1);
+            match attempts.get() {
+                1 => Err(UpbitResponseError::TooManyApiCall),
+                2 => Err(UpbitResponseError::Unreachable),
+                _ => Ok(attempts.get()),
+            }
+        }).await;
+        assert!(matches!(result, Ok(3)));
+    }
+
+    #[tokio::test]
+    async fn open_breaker_fails_without_sending_a_request() {
+        let breaker = breaker_of(RateGroup::Order);
+        for _ in 0..5 {
+            breaker.record_failure();
+        }
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = retry_transient_errors(RateGroup::Order, || async {
+            attempts.set(attempts.get() + 1);
+            Ok(())
+        }).await;
+        breaker.record_success();
+        assert!(matches!(result, Err(UpbitResponseError::CircuitOpen)));
+        assert_eq!(attempts.get(), 0);
+    }
 }
@@ -0,0 +1,107 @@
+use std::fmt;
+use std::time::Duration;
+use crate::upbit::UpbitAccount;
+use crate::upbit::api::{cancel_order, cancel_withdraw, get_api_keys, get_balances, UpbitResponseError};
+use crate::upbit::journal::{now_millis, parse_timestamp_millis};
+
+/// # API 키 권한
+/// UPBit은 키의 권한을 따로 알려주지 않으므로, 아무 효과가 없는 요청을 보내 응답으로 판단합니다.
+/// - View: 자산 조회 (계좌 잔고 조회가 성공하면 권한 있음)
+/// - Trade: 주문하기 (임의 UUID의 주문 취소가 order_not_found로 거절되면 권한 있음)
+/// - Withdraw: 출금하기 (임의 UUID의 출금 취소가 withdraw_not_found로 거절되면 권한 있음)
+///
+/// out_of_scope로 거절되면 권한이 없고, 그 밖의 응답은 권한을 판단할 수 없으므로 오류로 처리합니다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyPermission {
+    View,
+    Trade,
+    Withdraw,
+}
+
+impl fmt::Display for KeyPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyPermission::View => f.write_str("자산 조회"),
+            KeyPermission::Trade => f.write_str("주문하기"),
+            KeyPermission::Withdraw => f.write_str("출금하기"),
+        }
+    }
+}
+
+/// # API 키 점검 결과
+/// expires_at은 만료 시각(Unix 밀리초)이며, 만료 시각을 해석하지 못하면 None입니다.
+#[derive(Debug, Clone)]
+pub struct KeyReport {
+    pub access_key: String,
+    pub expire_at: String,
+    pub expires_at: Option<i64>,
+    pub permissions: Vec<KeyPermission>,
+}
+
+impl KeyReport {
+    /// 만료까지 남은 시간, 이미 만료되었으면 0입니다.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at.map(|expires_at| Duration::from_millis((expires_at - now_millis()).max(0) as u64))
+    }
+
+    pub fn has(&self, permission: KeyPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// 실거래 모드로 실행해도 되는지 확인합니다.
+    /// 주문하기 권한이 없거나, 만료 시각을 알 수 없거나, min_validity 안에 만료되는 키는 거절합니다.
+    pub fn check_live(&self, min_validity: Duration) -> Result<(), String> {
+        if !self.has(KeyPermission::Trade) {
+            return Err("API 키에 주문하기 권한이 없습니다.".to_string());
+        }
+        match self.remaining() {
+            None => Err(format!("API 키 만료 시각({})을 해석할 수 없습니다.", self.expire_at)),
+            Some(remaining) if remaining < min_validity => Err(format!(
+                "API 키가 {}에 만료됩니다. (남은 기간 {}일, 최소 {}일)",
+                self.expire_at, remaining.as_secs() / 86_400, min_validity.as_secs() / 86_400)),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// # API 키 점검
+/// 설정된 키가 API 키 목록에 있는지 확인하고, 만료 시각과 권한을 조회합니다.
+pub async fn inspect_api_key(account: &UpbitAccount) -> Result<KeyReport, String> {
+    let access_key = account.access_key.expose();
+    let keys = get_api_keys(account).await
+        .map_err(|e| format!("API 키 목록을 불러올 수 없습니다: {e}"))?;
+    let Some(key) = keys.into_iter().find(|key| key.access_key == access_key) else {
+        return Err("설정된 키가 API 키 목록에 없습니다.".to_string());
+    };
+
+    let mut permissions = Vec::new();
+    if is_permitted(KeyPermission::View, get_balances(account).await.map(|_| ()), None)? {
+        permissions.push(KeyPermission::View);
+    }
+    let probe = cancel_order(account, &uuid::Uuid::new_v4().to_string()).await.map(|_| ());
+    if is_permitted(KeyPermission::Trade, probe, Some("order_not_found"))? {
+        permissions.push(KeyPermission::Trade);
+    }
+    let probe = cancel_withdraw(account, &uuid::Uuid::new_v4().to_string()).await.map(|_| ());
+    if is_permitted(KeyPermission::Withdraw, probe, Some("withdraw_not_found"))? {
+        permissions.push(KeyPermission::Withdraw);
+    }
+
+    Ok(KeyReport {
+        access_key: format!("{}…", access_key.chars().take(6).collect::<String>()),
+        expires_at: parse_timestamp_millis(&key.expire_at),
+        expire_at: key.expire_at,
+        permissions,
+    })
+}
+
+// 권한이 없으면 out_of_scope로 거절됩니다. 권한이 있으면 요청이 성공하거나, 없는 대상을 가리켰으므로 not_found로 거절됩니다.
+// 그 밖의 거절은 권한 때문인지 알 수 없으므로 권한이 있다고 보지 않고 오류로 처리합니다.
+fn is_permitted(permission: KeyPermission, result: Result<(), UpbitResponseError>, not_found: Option<&str>) -> Result<bool, String> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(UpbitResponseError::Rejected(name, _)) if name == "out_of_scope" => Ok(false),
+        Err(UpbitResponseError::Rejected(name, _)) if Some(name.as_str()) == not_found => Ok(true),
+        Err(e) => Err(format!("{permission} 권한을 확인할 수 없습니다: {e}")),
+    }
+}
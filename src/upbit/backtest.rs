@@ -0,0 +1,108 @@
+use crate::upbit::executor::PaperAccount;
+use crate::upbit::response::CandleData;
+use crate::upbit::quote::QuoteCurrency;
+use crate::upbit::sizing::PositionSizing;
+use crate::upbit::strategy::{is_buy_confirmed, is_buy_signal, is_sell_signal, Confirmations};
+
+#[derive(Debug)]
+pub enum BacktestSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug)]
+pub struct BacktestTrade {
+    pub side: BacktestSide,
+    pub candle_date_time_kst: String,
+    pub price: f64,
+    pub volume: f64,
+}
+
+#[derive(Debug)]
+pub struct BacktestReport {
+    pub market: String,
+    pub trades: Vec<BacktestTrade>,
+    // 초기 잔고와 최종 평가액은 호가 통화 기준입니다.
+    pub initial_cash: f64,
+    pub final_equity: f64,
+    // 매수-매도 한 쌍을 한 거래로 보고 계산한 수익률(%) 목록
+    pub round_trip_returns: Vec<f64>,
+}
+
+impl BacktestReport {
+    pub fn total_return(&self) -> f64 {
+        (self.final_equity / self.initial_cash - 1.0) * 100.0
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.round_trip_returns.is_empty() {
+            return None;
+        }
+        let wins = self.round_trip_returns.iter().filter(|r| **r > 0.0).count();
+        Some(wins as f64 / self.round_trip_returns.len() as f64)
+    }
+}
+
+/// # 백테스트
+/// 최신 데이터가 앞에 오는 업비트 캔들 데이터를 과거부터 한 칸씩 진행하며 window 크기만큼 잘라 매수/매도 조건을 판단합니다.
+/// 체결은 해당 캔들의 종가로 처리하며, 매수 금액은 sizing으로 정합니다. initial_cash는 마켓의 호가 통화 기준입니다.
+/// 매수 신호는 confirmations의 추가 확인 조건도 만족해야 합니다.
+pub fn run_backtest(candle_data: &[CandleData], window: usize, initial_cash: f64, sizing: &PositionSizing, confirmations: &Confirmations) -> BacktestReport {
+    if window < 15 || window > candle_data.len() {
+        panic!("window는 15 이상이며 입력 데이터의 크기까지만 허용됩니다.");
+    }
+
+    let market = candle_data[0].market.clone();
+    let quote = QuoteCurrency::of_market(&market);
+    // 원화 환산은 하지 않으므로 환산 비율은 의미가 없습니다.
+    let mut paper = PaperAccount::with_cash(quote, initial_cash, 0.0);
+    let mut trades = Vec::new();
+    let mut round_trip_returns = Vec::new();
+    let mut entry_price = None;
+
+    for start in (0..=candle_data.len() - window).rev() {
+        let slice = &candle_data[start..start + window];
+        let current = &candle_data[start];
+
+        if entry_price.is_none() && is_buy_signal(slice) && is_buy_confirmed(slice, confirmations) {
+            let equity = paper.cash(quote) + paper.balance_of(&market).unwrap_or(0.0) * current.trade_price;
+            let budget = sizing.position_size(paper.cash(quote), equity, slice);
+            if budget < quote.min_order() {
+                continue;
+            }
+            if let Ok(volume) = paper.fill_buy(&market, budget, current.trade_price) {
+                entry_price = Some(current.trade_price);
+                trades.push(BacktestTrade {
+                    side: BacktestSide::Buy,
+                    candle_date_time_kst: current.candle_date_time_kst.clone(),
+                    price: current.trade_price,
+                    volume,
+                });
+            }
+        } else if let Some(entry) = entry_price {
+            if is_sell_signal(slice) {
+                if let Ok(volume) = paper.fill_sell(&market, 100.0, current.trade_price) {
+                    entry_price = None;
+                    round_trip_returns.push((current.trade_price / entry - 1.0) * 100.0);
+                    trades.push(BacktestTrade {
+                        side: BacktestSide::Sell,
+                        candle_date_time_kst: current.candle_date_time_kst.clone(),
+                        price: current.trade_price,
+                        volume,
+                    });
+                }
+            }
+        }
+    }
+
+    let last_price = candle_data[0].trade_price;
+    let final_equity = paper.cash(quote) + paper.balance_of(&market).unwrap_or(0.0) * last_price;
+
+    BacktestReport {
+        market,
+        trades,
+        initial_cash,
+        final_equity,
+        round_trip_returns,
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::{Mutex, OnceLock};
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+use crate::upbit::rate_limit::RateGroup;
+
+// 연속으로 이만큼 실패하면 차단합니다.
+const FAILURE_THRESHOLD: u32 = 5;
+// 첫 차단 시간, 다시 실패할 때마다 두 배로 늘어납니다.
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// 시험 요청이 결과를 알리지 않고 사라졌을 때(시간 초과로 취소 등) 다음 시험 요청을 보내기까지 기다리는 시간
+const TRIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CircuitState {
+    // 정상
+    Closed,
+    // 차단 중, 요청은 차단이 풀릴 때까지 기다립니다.
+    Open,
+    // 차단 시간이 지나 요청 하나를 시험 삼아 보내는 중, 나머지 요청은 결과를 기다립니다.
+    HalfOpen,
+}
+
+#[derive(Default)]
+struct BreakerInner {
+    consecutive_failures: u32,
+    // 정상으로 돌아오기 전까지 연속으로 차단된 횟수
+    trips: u32,
+    open_until: Option<Instant>,
+    // 반열림 상태에서 시험 요청을 보낸 시각
+    trial_started: Option<Instant>,
+}
+
+/// # 회로 차단기
+/// 요청 그룹별로 연속 실패를 세어, 기준을 넘으면 지수적으로 늘어나는 시간(지터 포함) 동안 요청을 막습니다.
+/// 차단 시간이 지나면 요청 하나만 시험 삼아 보내고, 성공하면 정상 상태로 돌아오며 실패하면 다시 차단합니다.
+#[derive(Default)]
+pub struct CircuitBreaker {
+    inner: Mutex<BreakerInner>,
+    // 시험 요청의 결과를 기다리는 요청을 깨웁니다.
+    settled: Notify,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CircuitSnapshot {
+    pub group: RateGroup,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+}
+
+impl CircuitBreaker {
+    pub fn state(&self) -> CircuitState {
+        let inner = self.inner.lock().unwrap();
+        match inner.open_until {
+            None => CircuitState::Closed,
+            Some(until) if Instant::now() < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// 차단 중이면 차단이 풀릴 때까지 기다립니다.
+    /// 반열림 상태에서는 먼저 온 요청 하나만 시험 요청으로 보내고, 나머지는 시험 요청의 결과가 나올 때까지 기다립니다.
+    pub async fn ready(&self) {
+        loop {
+            let settled = self.settled.notified();
+            let wait_until = {
+                let mut inner = self.inner.lock().unwrap();
+                let now = Instant::now();
+                match (inner.open_until, inner.trial_started) {
+                    (None, _) => return,
+                    (Some(until), _) if now < until => until,
+                    (Some(_), Some(started)) if now < started + TRIAL_TIMEOUT => started + TRIAL_TIMEOUT,
+                    (Some(_), _) => {
+                        inner.trial_started = Some(now);
+                        return;
+                    }
+                }
+            };
+            tokio::select! {
+                _ = settled => {}
+                _ = tokio::time::sleep_until(wait_until) => {}
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.inner.lock().unwrap() = BreakerInner::default();
+        self.settled.notify_waiters();
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        let half_open = inner.open_until.is_some_and(|until| Instant::now() >= until);
+        if inner.consecutive_failures >= FAILURE_THRESHOLD && (inner.open_until.is_none() || half_open) {
+            let backoff = BASE_BACKOFF.saturating_mul(1 << inner.trips.min(16)).min(MAX_BACKOFF);
+            inner.open_until = Some(Instant::now() + backoff.mul_f64(jitter()));
+            inner.trial_started = None;
+            inner.trips += 1;
+        }
+        drop(inner);
+        self.settled.notify_waiters();
+    }
+
+    fn snapshot(&self, group: RateGroup) -> CircuitSnapshot {
+        CircuitSnapshot {
+            group,
+            state: self.state(),
+            consecutive_failures: self.inner.lock().unwrap().consecutive_failures,
+        }
+    }
+}
+
+// 여러 요청이 동시에 다시 몰리지 않도록 차단 시간을 0.5배에서 1배 사이로 흩뜨립니다.
+fn jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or(0);
+    0.5 + (nanos % 1000) as f64 / 2000.0
+}
+
+pub fn breaker_of(group: RateGroup) -> &'static CircuitBreaker {
+    static QUOTATION: OnceLock<CircuitBreaker> = OnceLock::new();
+    static EXCHANGE: OnceLock<CircuitBreaker> = OnceLock::new();
+    static ORDER: OnceLock<CircuitBreaker> = OnceLock::new();
+
+    match group {
+        RateGroup::Quotation => QUOTATION.get_or_init(CircuitBreaker::default),
+        RateGroup::Exchange => EXCHANGE.get_or_init(CircuitBreaker::default),
+        RateGroup::Order => ORDER.get_or_init(CircuitBreaker::default),
+    }
+}
+
+pub fn circuit_snapshots() -> Vec<CircuitSnapshot> {
+    [RateGroup::Quotation, RateGroup::Exchange, RateGroup::Order]
+        .into_iter()
+        .map(|group| breaker_of(group).snapshot(group))
+        .collect()
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+use crate::upbit::market_filter::MarketFilter;
+use crate::upbit::risk::{ExitRules, RiskLimits};
+use crate::upbit::scanner::ScanOptions;
+use crate::upbit::scoring::ScoringConfig;
+use crate::upbit::sizing::PositionSizing;
+use crate::upbit::strategy::Confirmations;
+use crate::upbit::trade_flow::FlowConditions;
+
+/// # 서비스 설정
+/// 자동매매 서비스 실행에 필요한 설정을 모아둡니다.
+#[derive(Debug, Clone)]
+pub struct ServiceConfig {
+    // 종료 시 진행 중인 주문 작업을 기다릴 최대 시간
+    pub drain_timeout: Duration,
+    // 종료 시 미체결 지정가 주문을 취소할지 여부 (실거래 모드 전용)
+    pub cancel_open_orders: bool,
+    pub scan: ScanOptions,
+    // 보유 종목에 매 스캔마다 적용할 청산 규칙
+    pub exits: ExitRules,
+    // 새 주문에 적용할 포트폴리오 위험 한도
+    pub risk: RiskLimits,
+    // 매수 금액 결정 방식
+    pub sizing: PositionSizing,
+    // 매수 후보 점수 가중치와 스캔당 최대 매수 종목 수
+    pub scoring: ScoringConfig,
+    // 최근 체결 흐름을 이용한 추가 매수/매도 조건
+    pub flow: FlowConditions,
+    // 캔들 지표를 이용한 추가 매수 확인 조건
+    pub confirmations: Confirmations,
+    // 매수 대상 마켓 조건
+    pub markets: MarketFilter,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        ServiceConfig {
+            drain_timeout: Duration::from_secs(30),
+            cancel_open_orders: false,
+            scan: ScanOptions::default(),
+            exits: ExitRules::default(),
+            risk: RiskLimits::default(),
+            sizing: PositionSizing::default(),
+            scoring: ScoringConfig::default(),
+            flow: FlowConditions::default(),
+            confirmations: Confirmations::default(),
+            markets: MarketFilter::default(),
+        }
+    }
+}
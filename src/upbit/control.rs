@@ -0,0 +1,323 @@
+use std::sync::Arc;
+use actix_web::{get, post, web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use serde::Serialize;
+use crate::upbit::dashboard;
+use crate::upbit::api::{CandleUnit, get_candle_data, guaranteed_get_price_of};
+use crate::upbit::circuit_breaker::{circuit_snapshots, CircuitSnapshot};
+use crate::upbit::executor::OrderExecutor;
+use crate::upbit::journal::{now_millis, JournalEntry, OrderSide};
+use crate::upbit::ops::{BandOptions, BandValue, DmiValue, MacdComponent, SarOptions, MacdCross, MacdDivergence, MacdOptions, MacdValue, RsiDivergenceCheckMode, latest_value};
+use crate::upbit::response::CandleDataOperation;
+use crate::upbit::portfolio::fetch_portfolio;
+use crate::upbit::risk::RiskSnapshot;
+use crate::upbit::scoring::{score_buy_candidate, ScoreWeights, SignalScore};
+use crate::upbit::status::{RiskBlockRecord, ServiceState};
+use crate::upbit::strategy::{is_buy_signal, is_sell_signal};
+
+pub(super) struct ControlContext {
+    pub(super) state: Arc<ServiceState>,
+    pub(super) executor: OrderExecutor,
+    scoring: ScoreWeights,
+    token: String,
+}
+
+#[derive(Serialize)]
+struct SignalSnapshot {
+    market: String,
+    price: f64,
+    rsi: f64,
+    ewm_mean: f64,
+    std: f64,
+    buy: bool,
+    sell: bool,
+    score: Option<SignalScore>,
+    macd: MacdSnapshot,
+    bands: BandSnapshot,
+    trend: TrendSnapshot,
+    volume: VolumeSnapshot,
+}
+
+// OBV, 기본 기간(20)의 이동 VWAP과 상대 거래량, 당일 VWAP, 기본 기간(14)의 MFI
+#[derive(Serialize)]
+struct VolumeSnapshot {
+    obv: Option<f64>,
+    vwap: Option<f64>,
+    session_vwap: Option<f64>,
+    mfi: Option<f64>,
+    relative_volume: Option<f64>,
+}
+
+// 기본 기간(14)의 ATR과 DMI, 기본 설정(0.02, 0.2)의 파라볼릭 SAR
+#[derive(Serialize)]
+struct TrendSnapshot {
+    atr: Option<f64>,
+    dmi: Option<DmiValue>,
+    sar: Option<f64>,
+    sar_uptrend: Option<bool>,
+}
+
+// 기본 설정(20, 2σ, 1.5 ATR)으로 구한 볼린저 밴드와 켈트너 채널
+#[derive(Serialize)]
+struct BandSnapshot {
+    bollinger: Option<BandValue>,
+    keltner: Option<BandValue>,
+    percent_b: Option<f64>,
+    bandwidth: Option<f64>,
+    squeeze: bool,
+}
+
+// 기본 기간(12, 26, 9)으로 구한 MACD, 교차와 다이버전스는 최근 5개 데이터 이내만 봅니다.
+#[derive(Serialize)]
+struct MacdSnapshot {
+    value: Option<MacdValue>,
+    cross: Option<MacdCross>,
+    bullish_divergence: Option<MacdDivergence>,
+    bearish_divergence: Option<MacdDivergence>,
+}
+
+// 제어용 요청은 "Authorization: Bearer <token>" 헤더가 있어야 합니다.
+fn is_authorized(request: &HttpRequest, token: &str) -> bool {
+    request
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| given == token)
+}
+
+#[get("/status")]
+async fn status(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(context.state.snapshot())
+}
+
+#[get("/positions")]
+async fn positions(context: web::Data<ControlContext>) -> impl Responder {
+    match context.executor.positions().await {
+        Ok(positions) => HttpResponse::Ok().json(positions),
+        Err(e) => HttpResponse::ServiceUnavailable().body(format!("잔고를 불러올 수 없습니다: {e}")),
+    }
+}
+
+#[get("/portfolio")]
+async fn portfolio(context: web::Data<ControlContext>) -> impl Responder {
+    match fetch_portfolio(&context.executor, &context.state.journal.entries()).await {
+        Ok(portfolio) => HttpResponse::Ok().json(portfolio),
+        Err(e) => HttpResponse::ServiceUnavailable().body(format!("포트폴리오를 평가할 수 없습니다: {e}")),
+    }
+}
+
+#[get("/reconciliation")]
+async fn reconciliation(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(context.state.reconciliation())
+}
+
+#[get("/orders")]
+async fn orders(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(context.state.journal.entries())
+}
+
+#[get("/orders/pending")]
+async fn pending_orders(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(context.state.orders.pending())
+}
+
+#[get("/candidates")]
+async fn candidates(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(context.state.candidates())
+}
+
+#[get("/signals/{market}")]
+async fn signals(market: web::Path<String>, context: web::Data<ControlContext>) -> impl Responder {
+    let market = market.into_inner();
+    let candle_data = match get_candle_data(&market, &CandleUnit::Min1, 200).await {
+        Ok(candle_data) if candle_data.len() >= 15 => candle_data,
+        _ => return HttpResponse::NotFound().body(format!("{market}의 캔들 데이터를 불러올 수 없습니다.")),
+    };
+
+    HttpResponse::Ok().json(SignalSnapshot {
+        market,
+        price: candle_data[0].trade_price,
+        rsi: candle_data.get_rsi(),
+        ewm_mean: candle_data.get_ewm_mean(),
+        std: candle_data.get_std(),
+        buy: is_buy_signal(&candle_data),
+        sell: is_sell_signal(&candle_data),
+        score: score_buy_candidate(&candle_data, &context.scoring),
+        macd: {
+            let options = MacdOptions::default();
+            let macd = candle_data.get_macd(&options);
+            MacdSnapshot {
+                value: macd.latest(),
+                cross: macd.last_cross(5),
+                bullish_divergence: candle_data.get_macd_divergence(&options, &MacdComponent::Line, &RsiDivergenceCheckMode::Minpoint, &5),
+                bearish_divergence: candle_data.get_macd_divergence(&options, &MacdComponent::Line, &RsiDivergenceCheckMode::Peak, &5),
+            }
+        },
+        bands: {
+            let options = BandOptions::default();
+            BandSnapshot {
+                bollinger: candle_data.get_bollinger_bands(&options).latest(),
+                keltner: candle_data.get_keltner_channels(&options).latest(),
+                percent_b: candle_data.get_percent_b(&options),
+                bandwidth: candle_data.get_bandwidth(&options),
+                squeeze: candle_data.check_squeeze(&options),
+            }
+        },
+        trend: {
+            let sar = candle_data.get_parabolic_sar(&SarOptions::default()).latest();
+            TrendSnapshot {
+                atr: candle_data.get_atr(14),
+                dmi: candle_data.get_dmi(14).latest(),
+                sar: sar.map(|(sar, _)| sar),
+                sar_uptrend: sar.map(|(_, uptrend)| uptrend),
+            }
+        },
+        volume: VolumeSnapshot {
+            obv: latest_value(&candle_data.get_obv_series()),
+            vwap: latest_value(&candle_data.get_vwap_series(20)),
+            session_vwap: latest_value(&candle_data.get_session_vwap_series()),
+            mfi: latest_value(&candle_data.get_mfi_series(14)),
+            relative_volume: candle_data.get_relative_volume(20),
+        },
+    })
+}
+
+#[post("/pause")]
+async fn pause(request: HttpRequest, context: web::Data<ControlContext>) -> impl Responder {
+    if !is_authorized(&request, &context.token) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    context.state.set_paused(true);
+    HttpResponse::Ok().json(context.state.snapshot())
+}
+
+#[post("/resume")]
+async fn resume(request: HttpRequest, context: web::Data<ControlContext>) -> impl Responder {
+    if !is_authorized(&request, &context.token) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    context.state.set_paused(false);
+    HttpResponse::Ok().json(context.state.snapshot())
+}
+
+#[derive(Serialize)]
+struct RiskStatus {
+    #[serde(flatten)]
+    snapshot: RiskSnapshot,
+    blocks: Vec<RiskBlockRecord>,
+}
+
+#[get("/risk")]
+async fn risk(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(RiskStatus {
+        snapshot: context.state.risk.snapshot(),
+        blocks: context.state.risk_blocks(),
+    })
+}
+
+#[derive(Serialize)]
+struct HealthStatus {
+    degraded: bool,
+    circuits: Vec<CircuitSnapshot>,
+}
+
+#[get("/health")]
+async fn health(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(HealthStatus {
+        degraded: context.state.is_degraded(),
+        circuits: circuit_snapshots(),
+    })
+}
+
+#[post("/kill")]
+async fn kill(request: HttpRequest, context: web::Data<ControlContext>) -> impl Responder {
+    if !is_authorized(&request, &context.token) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    context.state.set_killed(true);
+    HttpResponse::Ok().json(context.state.snapshot())
+}
+
+#[post("/kill/release")]
+async fn release_kill(request: HttpRequest, context: web::Data<ControlContext>) -> impl Responder {
+    if !is_authorized(&request, &context.token) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    context.state.set_killed(false);
+    HttpResponse::Ok().json(context.state.snapshot())
+}
+
+#[post("/liquidate/{market}")]
+async fn liquidate(request: HttpRequest, market: web::Path<String>, context: web::Data<ControlContext>) -> impl Responder {
+    if !is_authorized(&request, &context.token) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    let market = market.into_inner();
+    match context.executor.balance_of(&market).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return HttpResponse::NotFound().body(format!("{market}의 보유량이 없습니다.")),
+        Err(e) => return HttpResponse::ServiceUnavailable().body(format!("잔고를 불러올 수 없습니다: {e}")),
+    }
+
+    let identifier = format!("yipir-{market}-liquidate-{}", now_millis());
+    let Some(_guard) = context.state.orders.try_lock(&market, OrderSide::Ask, &identifier) else {
+        return HttpResponse::Conflict().body(format!("{market}에 진행 중인 주문이 있습니다."));
+    };
+    let reference_price = match guaranteed_get_price_of(&market).await {
+        Ok(price) => price,
+        Err(e) => return HttpResponse::ServiceUnavailable().body(format!("{market}의 현재가를 불러올 수 없습니다: {e}")),
+    };
+    let result = context.executor.sell_market_order(&market, 100.0, Some(&identifier)).await;
+    let entry = JournalEntry {
+        timestamp: now_millis(),
+        market,
+        side: OrderSide::Ask,
+        amount: 100.0,
+        reference_price,
+        paper: context.executor.is_paper(),
+        error: result.as_ref().err().cloned(),
+        reason: None,
+        fill: result.ok().flatten(),
+        identifier: Some(identifier),
+    };
+    context.state.record_trade(entry.clone());
+
+    match entry.error {
+        None => HttpResponse::Ok().json(entry),
+        Some(_) => HttpResponse::InternalServerError().json(entry),
+    }
+}
+
+/// # 제어 API 서버 실행
+/// bind 주소에서 상태 조회 및 제어용 HTTP 서버를 실행합니다.
+/// 조회(GET)는 인증 없이, 제어(POST)는 token으로 보호됩니다.
+pub fn spawn_control_server(bind: &str, state: Arc<ServiceState>, executor: OrderExecutor, scoring: ScoreWeights, token: String) -> std::io::Result<tokio::task::JoinHandle<std::io::Result<()>>> {
+    let context = web::Data::new(ControlContext { state, executor, scoring, token });
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(context.clone())
+            .service(status)
+            .service(positions)
+            .service(portfolio)
+            .service(reconciliation)
+            .service(pending_orders)
+            .service(orders)
+            .service(signals)
+            .service(candidates)
+            .service(pause)
+            .service(resume)
+            .service(liquidate)
+            .service(risk)
+            .service(health)
+            .service(kill)
+            .service(release_kill)
+            .configure(dashboard::configure)
+    })
+        .workers(1)
+        .bind(bind)?
+        .run();
+
+    Ok(tokio::spawn(server))
+}
@@ -0,0 +1,82 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use futures_util::stream;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use crate::upbit::api::{CandleUnit, get_candle_data};
+use crate::upbit::control::ControlContext;
+use crate::upbit::journal::JournalEntry;
+use crate::upbit::ops::{get_ewm_mean_series, get_rsi_series};
+
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+#[derive(Serialize)]
+struct ChartData {
+    market: String,
+    timestamps: Vec<i64>,
+    price: Vec<f64>,
+    rsi: Vec<Option<f64>>,
+    ewm_mean: Vec<Option<f64>>,
+    markers: Vec<JournalEntry>,
+}
+
+#[get("/")]
+async fn index() -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(DASHBOARD_HTML)
+}
+
+#[get("/chart/{market}")]
+async fn chart(market: web::Path<String>, context: web::Data<ControlContext>) -> impl Responder {
+    let market = market.into_inner();
+    let candle_data = match get_candle_data(&market, &CandleUnit::Min1, 200).await {
+        Ok(candle_data) if candle_data.len() >= 15 => candle_data,
+        _ => return HttpResponse::NotFound().body(format!("{market}의 캔들 데이터를 불러올 수 없습니다.")),
+    };
+
+    // 업비트 캔들 데이터는 최신 순이므로 뒤집어서 시간 순으로 맞춥니다.
+    let timestamps = candle_data.iter().rev().map(|data| data.timestamp).collect::<Vec<i64>>();
+    let price = candle_data.iter().rev().map(|data| data.trade_price).collect::<Vec<f64>>();
+    let rsi = get_rsi_series(&candle_data).f64().unwrap().into_iter().collect();
+    let ewm_mean = get_ewm_mean_series(&candle_data).f64().unwrap().into_iter().collect();
+    let markers = context.state.journal.entries()
+        .into_iter()
+        .filter(|entry| entry.market == market && entry.timestamp >= timestamps[0])
+        .collect();
+
+    HttpResponse::Ok().json(ChartData { market, timestamps, price, rsi, ewm_mean, markers })
+}
+
+#[get("/equity")]
+async fn equity(context: web::Data<ControlContext>) -> impl Responder {
+    HttpResponse::Ok().json(context.state.equity_history())
+}
+
+#[get("/events")]
+async fn events(context: web::Data<ControlContext>) -> impl Responder {
+    let receiver = context.state.subscribe();
+    let event_stream = stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((Ok::<_, actix_web::Error>(web::Bytes::from(event)), receiver)),
+                // 느린 구독자는 밀린 이벤트를 건너뛰고 최신 이벤트부터 받습니다.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(event_stream)
+}
+
+/// 대시보드 페이지와 차트 데이터, 이벤트 스트림 경로를 등록합니다.
+pub fn configure(config: &mut web::ServiceConfig) {
+    config
+        .service(index)
+        .service(chart)
+        .service(equity)
+        .service(events);
+}
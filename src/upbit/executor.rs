@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use crate::upbit::UpbitAccount;
+use tokio::time::Duration;
+use crate::upbit::journal::Fill;
+use crate::upbit::quote::{QuoteCurrency, QuoteRates};
+use crate::upbit::response::Order;
+use crate::upbit::api::{buy_market_order, get_all_balances, get_balance_of, get_order_by_identifier, guaranteed_get_price_of, sell_market_order};
+
+/// # 보유 종목
+/// 실거래와 모의 거래 모두 같은 형식으로 보유 현황을 나타냅니다.
+/// 평균 매수가는 market의 호가 통화 기준입니다.
+/// volume은 미체결 주문에 묶인 locked 수량을 포함하며, 바로 주문할 수 있는 수량은 free_volume입니다.
+#[derive(Serialize, Debug, Clone)]
+pub struct Position {
+    pub market: String,
+    pub volume: f64,
+    pub locked: f64,
+    pub avg_buy_price: f64,
+}
+
+impl Position {
+    pub fn free_volume(&self) -> f64 {
+        (self.volume - self.locked).max(0.0)
+    }
+}
+
+#[derive(Debug)]
+struct PaperHolding {
+    volume: f64,
+    avg_buy_price: f64,
+}
+
+/// # 모의 계좌
+/// 실제 주문을 보내지 않고 잔고와 보유량만 메모리에서 관리합니다.
+/// 실거래 잔고와 마찬가지로 원화가 아닌 호가 통화(BTC, USDT)는 원화 마켓 보유량(KRW-BTC 등)으로 관리합니다.
+#[derive(Debug)]
+pub struct PaperAccount {
+    krw: f64,
+    holdings: HashMap<String, PaperHolding>,
+    identifiers: HashSet<String>,
+}
+
+impl PaperAccount {
+    pub fn new(krw: f64) -> PaperAccount {
+        PaperAccount { krw, holdings: HashMap::new(), identifiers: HashSet::new() }
+    }
+
+    /// quote 잔고 cash로 시작하는 모의 계좌, 원화 환산 평균 매수가는 krw_rate로 기록합니다.
+    pub fn with_cash(quote: QuoteCurrency, cash: f64, krw_rate: f64) -> PaperAccount {
+        match quote.krw_market() {
+            None => PaperAccount::new(cash),
+            Some(market) => {
+                let mut paper = PaperAccount::new(0.0);
+                paper.holdings.insert(market, PaperHolding { volume: cash, avg_buy_price: krw_rate });
+                paper
+            }
+        }
+    }
+
+    /// ticker가 통화 코드(KRW, BTC)이면 잔고를, 마켓 코드(KRW-BTC)이면 보유량을 반환합니다.
+    pub fn balance_of(&self, ticker: &str) -> Option<f64> {
+        if ticker == "KRW" {
+            return Some(self.krw);
+        }
+        let market = match QuoteCurrency::from_code(ticker).and_then(|quote| quote.krw_market()) {
+            Some(market) => market,
+            None => ticker.to_string(),
+        };
+        self.holdings
+            .get(&market)
+            .map(|holding| holding.volume)
+            .filter(|volume| *volume > 0.0)
+    }
+
+    pub fn cash(&self, quote: QuoteCurrency) -> f64 {
+        self.balance_of(quote.code()).unwrap_or(0.0)
+    }
+
+    // 호가 통화 잔고를 amount만큼 늘리거나 줄입니다. 원화가 아닌 호가 통화는 다 써도 평균 매수가를 남겨둡니다.
+    fn add_cash(&mut self, quote: QuoteCurrency, amount: f64) {
+        match quote.krw_market() {
+            None => self.krw += amount,
+            Some(market) => {
+                self.holdings
+                    .entry(market)
+                    .or_insert(PaperHolding { volume: 0.0, avg_buy_price: 0.0 })
+                    .volume += amount;
+            }
+        }
+    }
+
+    /// 실거래와 마찬가지로 이미 사용한 주문 식별자는 거절합니다.
+    pub fn register_identifier(&mut self, identifier: &str) -> Result<(), String> {
+        if !self.identifiers.insert(identifier.to_string()) {
+            return Err(format!("이미 접수된 주문입니다: {identifier}"));
+        }
+        Ok(())
+    }
+
+    /// 호가 통화 budget만큼 price에 체결된 것으로 처리합니다.
+    pub fn fill_buy(&mut self, ticker: &str, budget: f64, price: f64) -> Result<f64, String> {
+        let quote = QuoteCurrency::of_market(ticker);
+        if budget > self.cash(quote) {
+            return Err(format!("모의 계좌의 {} 잔고가 부족합니다.", quote.code()));
+        }
+
+        let volume = budget * (1.0 - quote.fee_rate()) / price;
+        self.add_cash(quote, -budget);
+        let holding = self.holdings
+            .entry(ticker.to_string())
+            .or_insert(PaperHolding { volume: 0.0, avg_buy_price: 0.0 });
+        holding.avg_buy_price = (holding.avg_buy_price * holding.volume + budget) / (holding.volume + volume);
+        holding.volume += volume;
+        Ok(volume)
+    }
+
+    /// 보유량의 ratio%를 price에 체결된 것으로 처리합니다.
+    pub fn fill_sell(&mut self, ticker: &str, ratio: f64, price: f64) -> Result<f64, String> {
+        if !(0.0..=100.0).contains(&ratio) {
+            return Err("판매 비율이 잘못되었습니다.".to_string());
+        }
+
+        let Some(holding) = self.holdings.get_mut(ticker).filter(|holding| holding.volume > 0.0) else {
+            return Err("판매할 보유량이 없습니다.".to_string());
+        };
+        let volume = holding.volume * ratio / 100.0;
+        holding.volume -= volume;
+        if holding.volume <= 0.0 {
+            self.holdings.remove(ticker);
+        }
+        let quote = QuoteCurrency::of_market(ticker);
+        self.add_cash(quote, volume * price * (1.0 - quote.fee_rate()));
+        Ok(volume)
+    }
+
+    pub fn positions(&self) -> Vec<Position> {
+        self.holdings
+            .iter()
+            .filter(|(_, holding)| holding.volume > 0.0)
+            .map(|(market, holding)| Position {
+                market: market.clone(),
+                volume: holding.volume,
+                locked: 0.0,
+                avg_buy_price: holding.avg_buy_price,
+            })
+            .collect()
+    }
+}
+
+/// # 호가 통화 기준 보유 종목
+/// 보유 종목을 quote 마켓 기준으로 바꿉니다. 다른 호가 통화로 기록된 평균 매수가는 rates로 환산하며,
+/// 환산할 수 없거나 quote로 거래할 수 없는 종목(호가 통화 자신 등)은 제외합니다.
+pub fn positions_in_quote(positions: Vec<Position>, quote: QuoteCurrency, rates: &QuoteRates) -> Vec<Position> {
+    positions
+        .into_iter()
+        .filter_map(|position| {
+            let (held_quote, base) = position.market.split_once('-')?;
+            if !quote.can_quote(base) {
+                return None;
+            }
+            let held_quote = QuoteCurrency::from_code(held_quote)?;
+            Some(Position {
+                market: format!("{}-{base}", quote.code()),
+                volume: position.volume,
+                locked: position.locked,
+                avg_buy_price: rates.convert(position.avg_buy_price, held_quote, quote)?,
+            })
+        })
+        .collect()
+}
+
+/// # 주문 실행기
+/// 실거래(Live)와 모의 거래(Paper)를 같은 인터페이스로 다룹니다.
+#[derive(Clone)]
+pub enum OrderExecutor {
+    Live(Arc<UpbitAccount>),
+    Paper(Arc<Mutex<PaperAccount>>),
+}
+
+impl OrderExecutor {
+    /// 보유량이 없으면 Ok(None), 잔고를 조회하지 못하면 에러를 반환합니다.
+    pub async fn balance_of(&self, ticker: &str) -> Result<Option<f64>, String> {
+        match self {
+            OrderExecutor::Live(account) => get_balance_of(account, ticker).await.map_err(|e| e.to_string()),
+            OrderExecutor::Paper(paper) => Ok(paper.lock().unwrap().balance_of(ticker)),
+        }
+    }
+
+    pub fn is_paper(&self) -> bool {
+        matches!(self, OrderExecutor::Paper(_))
+    }
+
+    /// 원화를 제외한 보유 종목 목록을 반환합니다. BTC, USDT 잔고도 KRW-BTC, KRW-USDT 보유 종목으로 나타납니다.
+    pub async fn positions(&self) -> Result<Vec<Position>, String> {
+        match self {
+            OrderExecutor::Live(account) => Ok(get_all_balances(account).await
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .filter(|balance| balance.currency != "KRW" && balance.balance + balance.locked > 0.0)
+                .map(|balance| Position {
+                    market: format!("{}-{}", balance.unit_currency, balance.currency),
+                    volume: balance.balance + balance.locked,
+                    locked: balance.locked,
+                    avg_buy_price: balance.avg_buy_price,
+                })
+                .collect()),
+            OrderExecutor::Paper(paper) => Ok(paper.lock().unwrap().positions()),
+        }
+    }
+
+    /// # 시장가 매수
+    /// identifier가 주어지면 중복 접수를 막고, 실거래에서는 주문이 체결 또는 취소될 때까지 기다립니다.
+    pub async fn buy_market_order(&self, ticker: &str, budget: f64, identifier: Option<&str>) -> Result<Option<Fill>, String> {
+        match self {
+            OrderExecutor::Live(account) => {
+                buy_market_order(account, ticker, budget, identifier).await?;
+                match identifier {
+                    Some(identifier) => Ok(wait_for_settlement(account, identifier).await),
+                    None => Ok(None),
+                }
+            }
+            OrderExecutor::Paper(paper) => {
+                let price = guaranteed_get_price_of(ticker).await.map_err(|e| e.to_string())?;
+                let mut paper = paper.lock().unwrap();
+                if let Some(identifier) = identifier {
+                    paper.register_identifier(identifier)?;
+                }
+                let volume = paper.fill_buy(ticker, budget, price)?;
+                println!("[모의] {ticker} 매수: {volume} @ {price}");
+                Ok(Some(Fill { price, volume, fee: budget * QuoteCurrency::of_market(ticker).fee_rate() }))
+            }
+        }
+    }
+
+    pub async fn sell_market_order(&self, ticker: &str, ratio: f64, identifier: Option<&str>) -> Result<Option<Fill>, String> {
+        match self {
+            OrderExecutor::Live(account) => {
+                sell_market_order(account, ticker, ratio, identifier).await?;
+                match identifier {
+                    Some(identifier) => Ok(wait_for_settlement(account, identifier).await),
+                    None => Ok(None),
+                }
+            }
+            OrderExecutor::Paper(paper) => {
+                let price = guaranteed_get_price_of(ticker).await.map_err(|e| e.to_string())?;
+                let mut paper = paper.lock().unwrap();
+                if let Some(identifier) = identifier {
+                    paper.register_identifier(identifier)?;
+                }
+                let volume = paper.fill_sell(ticker, ratio, price)?;
+                println!("[모의] {ticker} 매도: {volume} @ {price}");
+                Ok(Some(Fill { price, volume, fee: volume * price * QuoteCurrency::of_market(ticker).fee_rate() }))
+            }
+        }
+    }
+}
+
+// 주문이 대기(wait) 상태를 벗어날 때까지 최대 10초간 확인하고, 체결 내역을 합쳐 반환합니다.
+async fn wait_for_settlement(account: &UpbitAccount, identifier: &str) -> Option<Fill> {
+    let mut interval = tokio::time::interval(Duration::from_millis(500));
+    for _ in 0..20 {
+        interval.tick().await;
+        if let Ok(order) = get_order_by_identifier(account, identifier).await {
+            if order.state != "wait" && order.state != "watch" {
+                return fill_of(&order);
+            }
+        }
+    }
+    None
+}
+
+/// # 체결 내역 합치기
+/// 개별 주문 조회 결과의 체결 목록을 하나로 합치며, 체결이 없으면 None을 반환합니다.
+pub fn fill_of(order: &Order) -> Option<Fill> {
+    let volume = order.trades.iter().map(|trade| trade.volume).sum::<f64>();
+    if volume <= 0.0 {
+        return None;
+    }
+    let funds = order.trades.iter().map(|trade| trade.funds).sum::<f64>();
+    Some(Fill { price: funds / volume, volume, fee: order.paid_fee })
+}
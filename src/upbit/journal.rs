@@ -0,0 +1,140 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+/// # 거래 기록
+/// amount는 매수의 경우 사용한 원화, 매도의 경우 보유량 대비 매도 비율(%)입니다.
+/// reference_price는 주문을 결정한 시점의 가격이며 실제 체결가와 다를 수 있습니다.
+/// reason은 전략 신호가 아닌 손절, 익절 등의 청산 규칙으로 낸 주문에만 기록됩니다.
+/// fill은 체결 내역이며, 체결을 확인하지 못한 주문은 비어 있습니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub timestamp: i64,
+    pub market: String,
+    pub side: OrderSide,
+    pub amount: f64,
+    pub reference_price: f64,
+    pub paper: bool,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub fill: Option<Fill>,
+    // 주문에 사용한 식별자, 재시작 시 거래소 주문 내역과 맞춰보는 데 사용합니다.
+    #[serde(default)]
+    pub identifier: Option<String>,
+}
+
+/// # 체결 내역
+/// 여러 번에 나누어 체결된 주문은 거래량 가중 평균 가격으로 합쳐 기록합니다.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Fill {
+    pub price: f64,
+    pub volume: f64,
+    pub fee: f64,
+}
+
+/// # 거래 일지
+/// 봇이 보낸 주문을 메모리에 보관하고 JSON Lines 파일에 한 줄씩 덧붙여 저장합니다.
+pub struct TradeJournal {
+    entries: Mutex<Vec<JournalEntry>>,
+    file: Mutex<File>,
+}
+
+impl TradeJournal {
+    /// path의 기존 기록을 불러오고, 이후 기록은 같은 파일에 덧붙입니다.
+    pub fn open(path: &Path) -> std::io::Result<TradeJournal> {
+        let entries = match File::open(path) {
+            Ok(file) => BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str::<JournalEntry>(&line).ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(TradeJournal {
+            entries: Mutex::new(entries),
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn record(&self, entry: JournalEntry) {
+        if let Ok(line) = serde_json::to_string(&entry) {
+            let mut file = self.file.lock().unwrap();
+            if let Err(e) = writeln!(file, "{line}") {
+                eprintln!("거래 일지를 기록할 수 없습니다: {e}");
+            }
+        }
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    /// 버퍼에 남은 기록을 디스크까지 씁니다.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let mut file = self.file.lock().unwrap();
+        file.flush()?;
+        file.sync_data()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// 기록을 timestamp 순서로 반환합니다. 시작 시 복구한 과거 주문은 파일 끝에 덧붙지만 원래 시각 자리에 놓입니다.
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        let mut entries = self.entries.lock().unwrap().clone();
+        entries.sort_by_key(|entry| entry.timestamp);
+        entries
+    }
+}
+
+pub fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// "2021-03-22T18:01:42+09:00" 꼴의 UPBit 시각을 Unix 밀리초로 바꿉니다.
+pub fn parse_timestamp_millis(timestamp: &str) -> Option<i64> {
+    let (date, rest) = timestamp.split_once('T')?;
+    let mut date_parts = date.split('-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+
+    let (time, offset_seconds) = match rest.strip_suffix('Z') {
+        Some(time) => (time, 0),
+        None => {
+            let (time, offset) = rest.split_at(rest.rfind(['+', '-'])?);
+            let (hours, minutes) = offset[1..].split_once(':')?;
+            let offset_seconds = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+            (time, if offset.starts_with('-') { -offset_seconds } else { offset_seconds })
+        }
+    };
+    let mut time_parts = time.split(':');
+    let hour = time_parts.next()?.parse::<i64>().ok()?;
+    let minute = time_parts.next()?.parse::<i64>().ok()?;
+    let second = time_parts.next()?.parse::<f64>().ok()? as i64;
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset_seconds;
+    Some(seconds * 1000)
+}
+
+// 1970-01-01부터 그레고리력 날짜까지의 일 수
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
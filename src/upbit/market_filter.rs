@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+use crate::upbit::quote::QuoteCurrency;
+use crate::upbit::response::{Ticker, TickerSnapshot};
+
+/// # 마켓 필터
+/// 매수 대상이 될 수 있는 마켓을 고릅니다.
+/// - quote: 호가 통화 (KRW, BTC, USDT)
+/// - exclude_warning: 투자 유의/유의 종목 제외
+/// - exclude_caution: 주의 종목 제외
+/// - min_trade_value_24h: 최근 24시간 거래대금 하한 (호가 통화 기준)
+/// - whitelist: 비어 있지 않으면 이 마켓들만 허용
+/// - blacklist: 항상 제외할 마켓
+/// - exclude_suspended_wallets: 입출금이 중단된 통화 제외 (입출금 현황은 계정 키가 있어야 조회할 수 있습니다.)
+///
+/// whitelist와 blacklist에는 마켓 코드(KRW-BTC)나 거래 대상 통화(BTC)를 쓸 수 있습니다.
+#[derive(Debug, Clone)]
+pub struct MarketFilter {
+    pub quote: QuoteCurrency,
+    pub exclude_warning: bool,
+    pub exclude_caution: bool,
+    pub min_trade_value_24h: Option<f64>,
+    pub whitelist: Vec<String>,
+    pub blacklist: Vec<String>,
+    pub exclude_suspended_wallets: bool,
+}
+
+impl Default for MarketFilter {
+    fn default() -> Self {
+        MarketFilter {
+            quote: QuoteCurrency::Krw,
+            exclude_warning: true,
+            exclude_caution: false,
+            min_trade_value_24h: None,
+            whitelist: Vec::new(),
+            blacklist: Vec::new(),
+            exclude_suspended_wallets: true,
+        }
+    }
+}
+
+impl MarketFilter {
+    /// 호가 통화가 맞는 마켓
+    pub fn quote_markets(&self, markets: &[Ticker]) -> Vec<String> {
+        markets
+            .iter()
+            .filter(|ticker| ticker.quote() == self.quote.code())
+            .map(|ticker| ticker.market.clone())
+            .collect()
+    }
+
+    /// 마켓이 매수 대상인지 확인합니다. 24시간 거래대금 하한이 있는데 snapshot이 없으면 제외합니다.
+    pub fn allows(&self, ticker: &Ticker, snapshot: Option<&TickerSnapshot>) -> bool {
+        if ticker.quote() != self.quote.code() {
+            return false;
+        }
+        if !self.whitelist.is_empty() && !matches_any(&self.whitelist, ticker) {
+            return false;
+        }
+        if matches_any(&self.blacklist, ticker) {
+            return false;
+        }
+        if self.exclude_warning && ticker.is_warned() {
+            return false;
+        }
+        if self.exclude_caution && ticker.has_caution() {
+            return false;
+        }
+        match self.min_trade_value_24h {
+            Some(min) => snapshot.is_some_and(|snapshot| snapshot.acc_trade_price_24h >= min),
+            None => true,
+        }
+    }
+
+    /// 조건을 모두 만족하는 마켓 목록
+    pub fn select(&self, markets: &[Ticker], snapshots: &[TickerSnapshot]) -> Vec<String> {
+        let snapshots = snapshots
+            .iter()
+            .map(|snapshot| (snapshot.market.as_str(), snapshot))
+            .collect::<HashMap<&str, &TickerSnapshot>>();
+        markets
+            .iter()
+            .filter(|ticker| self.allows(ticker, snapshots.get(ticker.market.as_str()).copied()))
+            .map(|ticker| ticker.market.clone())
+            .collect()
+    }
+
+    /// 입출금이 중단된 통화의 마켓을 뺍니다. exclude_suspended_wallets가 꺼져 있으면 그대로 반환합니다.
+    pub fn without_suspended(&self, markets: Vec<String>, suspended: &HashSet<String>) -> Vec<String> {
+        if !self.exclude_suspended_wallets {
+            return markets;
+        }
+        markets
+            .into_iter()
+            .filter(|market| market.split_once('-').is_none_or(|(_, base)| !suspended.contains(base)))
+            .collect()
+    }
+}
+
+fn matches_any(entries: &[String], ticker: &Ticker) -> bool {
+    entries.iter().any(|entry| entry == &ticker.market || entry == ticker.base())
+}
@@ -1,82 +1,512 @@
-use crate::upbit::api::{CandleUnit, get_all_tickers, guaranteed_get_candle_data, sell_market_order, get_balance_of, buy_market_order};
-use crate::upbit::ops::RsiDivergenceCheckMode;
-use crate::upbit::response::{CandleData, CandleDataOperation};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use crate::upbit::api::{CandleUnit, get_all_markets, get_orderbook, get_price_of, get_recent_trade_ticks, get_tickers, get_wallet_status};
+use crate::upbit::circuit_breaker::{breaker_of, CircuitState};
+use crate::upbit::config::ServiceConfig;
+use crate::upbit::executor::{positions_in_quote, OrderExecutor};
+use crate::upbit::journal::{now_millis, JournalEntry, OrderSide};
+use crate::upbit::order_state::order_identifier;
+use crate::upbit::portfolio::valuate;
+use crate::upbit::quote::{try_fetch_quote_rates, QuoteCurrency};
+use crate::upbit::rate_limit::RateGroup;
+use crate::upbit::risk::{ExitReason, Exposure, RiskBlock};
+use crate::upbit::scanner::scan_candles;
+use crate::upbit::secret::{SecretString, load_keystore, save_keystore};
+use crate::upbit::shutdown::{cancel_open_limit_orders, drain_order_tasks, ShutdownSummary};
+use crate::upbit::status::ServiceState;
+use crate::upbit::scoring::rank_buy_candidates;
+use crate::upbit::strategy::{is_buy_confirmed, is_buy_flow, is_sell_flow, is_sell_signal};
+use crate::upbit::trade_flow::TradeFlow;
+use crate::upbit::wallet::suspended_currencies;
 
-mod api;
-mod response;
-mod ops;
+// 입출금 현황은 자주 바뀌지 않으므로 이 간격마다 다시 조회합니다.
+const WALLET_STATUS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+pub mod api;
+pub mod api_key;
+pub mod response;
+pub mod ops;
+pub mod strategy;
+pub mod executor;
+pub mod backtest;
+pub mod journal;
+pub mod status;
+pub mod control;
+pub mod dashboard;
+pub mod shutdown;
+pub mod rate_limit;
+pub mod circuit_breaker;
+pub mod scanner;
+pub mod config;
+pub mod order_state;
+pub mod portfolio;
+pub mod orderbook;
+pub mod trade_flow;
+pub mod market_filter;
+pub mod quote;
+pub mod wallet;
+pub mod reconcile;
+pub mod risk;
+pub mod scoring;
+pub mod sizing;
+mod secret;
 
-#[derive(Clone)]
 pub struct UpbitAccount {
-    access_key: String,
-    secret_key: String,
+    access_key: SecretString,
+    secret_key: SecretString,
 }
 
 impl UpbitAccount {
     pub fn new(access_key: String, secret_key: String) -> UpbitAccount {
-        UpbitAccount { access_key, secret_key }
+        UpbitAccount {
+            access_key: SecretString::new(access_key),
+            secret_key: SecretString::new(secret_key),
+        }
+    }
+}
+
+impl fmt::Debug for UpbitAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpbitAccount")
+            .field("access_key", &self.access_key)
+            .field("secret_key", &self.secret_key)
+            .finish()
+    }
+}
+
+/// # 계정 정보 불러오기
+/// `YIPIR_KEYSTORE`가 지정되어 있으면 `YIPIR_KEYSTORE_PASSPHRASE`로 키스토어 파일을 복호화하고,
+/// 그렇지 않으면 `UPBIT_ACCESS_KEY`, `UPBIT_SECRET_KEY` 환경 변수에서 키를 읽습니다.
+pub fn load_account() -> Result<Arc<UpbitAccount>, String> {
+    use std::env;
+
+    if let Ok(keystore_path) = env::var("YIPIR_KEYSTORE") {
+        let passphrase = SecretString::new(
+            env::var("YIPIR_KEYSTORE_PASSPHRASE")
+                .map_err(|_| "YIPIR_KEYSTORE_PASSPHRASE가 설정되지 않았습니다.".to_string())?);
+        return load_keystore(std::path::Path::new(&keystore_path), passphrase.expose())
+            .map(Arc::new)
+            .map_err(|e| e.to_string());
+    }
+
+    match (env::var("UPBIT_ACCESS_KEY"), env::var("UPBIT_SECRET_KEY")) {
+        (Ok(access_key), Ok(secret_key)) => Ok(Arc::new(UpbitAccount::new(access_key, secret_key))),
+        _ => Err("UPBit 키가 설정되지 않았습니다. YIPIR_KEYSTORE 또는 UPBIT_ACCESS_KEY, UPBIT_SECRET_KEY를 지정하세요.".to_string()),
     }
 }
 
-pub async fn spawn_yipir_upbit_service() -> tokio::task::JoinHandle<()> {
+/// # 키스토어 만들기
+/// `UPBIT_ACCESS_KEY`, `UPBIT_SECRET_KEY`의 키를 `YIPIR_KEYSTORE_PASSPHRASE`로 암호화하여 path에 저장합니다.
+/// 이미 파일이 있으면 overwrite가 true일 때만 덮어씁니다.
+pub fn init_keystore(path: &std::path::Path, overwrite: bool) -> Result<(), String> {
+    use std::env;
+
+    if path.exists() && !overwrite {
+        return Err(format!("{}이(가) 이미 있습니다. 덮어쓰려면 --force를 지정하세요.", path.display()));
+    }
+    let passphrase = SecretString::new(
+        env::var("YIPIR_KEYSTORE_PASSPHRASE")
+            .map_err(|_| "YIPIR_KEYSTORE_PASSPHRASE가 설정되지 않았습니다.".to_string())?);
+    match (env::var("UPBIT_ACCESS_KEY"), env::var("UPBIT_SECRET_KEY")) {
+        (Ok(access_key), Ok(secret_key)) => {
+            let (access_key, secret_key) = (SecretString::new(access_key), SecretString::new(secret_key));
+            save_keystore(path, passphrase.expose(), access_key.expose(), secret_key.expose()).map_err(|e| e.to_string())
+        }
+        _ => Err("키스토어에 저장할 UPBIT_ACCESS_KEY, UPBIT_SECRET_KEY가 설정되지 않았습니다.".to_string()),
+    }
+}
+
+/// # 자동매매 서비스 실행
+/// 1초마다 config.markets의 호가 통화(기본 KRW) 마켓을 스캔하여 매수/매도 조건을 만족하는 종목을 주문합니다.
+/// 매수 금액과 노출은 호가 통화 기준이며, 평가액과 손익은 원화로 환산합니다.
+/// 매수 후보는 config.confirmations의 추가 확인 조건을 만족해야 하며, 점수 순으로 최대 config.scoring.top_n 종목까지 매수하며, 매수 금액은 config.sizing으로 정합니다.
+/// 보유 종목은 전략과 별개로 config.exits의 청산 규칙에 걸리면 전량 매도하며, 청산 규칙은 일시정지 중에도 매 틱 확인합니다.
+/// 입출금이 중단된 통화는 매수하지 않으며, 모의 거래에서도 계정 키가 있으면 입출금 현황을 조회합니다.
+/// 시작 전 reconcile에서 관리 제외로 표시한 종목은 청산 규칙과 전략 매도에서 제외합니다.
+/// 매수 주문은 config.risk의 위험 한도를 따르며, 매도 주문(전략 매도, 청산 규칙, 킬 스위치)은 한도와 관계없이 실행됩니다.
+/// 종료가 요청되면 새 신호 처리를 멈추고, 진행 중인 주문 작업을 config.drain_timeout까지 기다린 뒤 요약을 반환합니다.
+pub async fn spawn_yipir_upbit_service(executor: OrderExecutor, state: Arc<ServiceState>, config: ServiceConfig) -> tokio::task::JoinHandle<ShutdownSummary> {
     use tokio::{task, time};
     use tokio::time::Duration;
 
     task::spawn(async move {
         let mut interval = time::interval(Duration::from_secs(1));
-        let upbit_account = UpbitAccount::new(
-            String::from(" UPBit Access Key"),
-            String::from(" UPBit Secret Key "));
+        let mut shutdown = state.shutdown_receiver();
+        let mut order_tasks = JoinSet::new();
+        let journal_len_at_start = state.journal.len();
+        let mut scans = 0;
+        let quote = config.markets.quote;
+        let wallet_account = match &executor {
+            OrderExecutor::Live(account) => Some(Arc::clone(account)),
+            OrderExecutor::Paper(_) => load_account().ok(),
+        };
+        let mut suspended_wallets = HashSet::new();
+        let mut wallets_checked_at: Option<time::Instant> = None;
 
          loop {
-             interval.tick().await;
-             let all_tickers = get_all_tickers().await;
-             let mut candle_datas = Vec::new();
-             for ticker in all_tickers {
-                 candle_datas.push(guaranteed_get_candle_data(ticker.as_str(), CandleUnit::Min1, 200).await);
+             tokio::select! {
+                 _ = interval.tick() => {}
+                 _ = shutdown.changed() => {}
+             }
+             if state.is_shutting_down() {
+                 break;
              }
 
-             //구매 점수 높은 종목을 찾아 구매 시행
-             candle_datas
+             // 끝난 주문 작업은 정리합니다.
+             while order_tasks.try_join_next().is_some() {}
+
+             // 킬 스위치가 켜져 있으면 일시정지 여부와 관계없이 모든 보유 종목을 청산합니다.
+             // 환산 비율을 조회하지 못하면 호가 통화 마켓의 보유 종목만 청산하고, 잔고를 조회하지 못하면 다음 틱에 다시 시도합니다.
+             if state.risk.is_killed() {
+                 let rates = try_fetch_quote_rates().await.unwrap_or_default();
+                 let Ok(positions) = executor.positions().await else {
+                     continue;
+                 };
+                 for position in positions_in_quote(positions, quote, &rates) {
+                     let ticker = position.market.clone();
+                     if !state.risk.liquidation_ready(&ticker) {
+                         continue;
+                     }
+                     let identifier = state.risk.next_liquidation_identifier(&ticker);
+                     let Some(guard) = state.orders.try_lock(&ticker, OrderSide::Ask, &identifier) else {
+                         continue;
+                     };
+                     let cloned_executor = executor.clone();
+                     let cloned_state = Arc::clone(&state);
+                     order_tasks.spawn(async move {
+                         let _guard = guard;
+                         // 현재가를 모르거나, 주문할 수 있는 수량이 최소 주문 금액에 못 미치는 종목(잔량, 미체결 주문에 묶인 수량)은 나중에 다시 확인합니다.
+                         let reference_price = match get_price_of(&ticker).await {
+                             Ok(price) if position.free_volume() * price >= quote.min_order() => price,
+                             _ => {
+                                 cloned_state.risk.record_liquidation_failure(&ticker);
+                                 return;
+                             }
+                         };
+                         let result = cloned_executor.sell_market_order(&ticker, 100.0, Some(&identifier)).await;
+                         // 실패한 주문은 연속 실패의 첫 번째만 일지에 남깁니다.
+                         let failures = match &result {
+                             Ok(_) => {
+                                 cloned_state.risk.record_liquidation_success(&ticker);
+                                 0
+                             }
+                             Err(_) => cloned_state.risk.record_liquidation_failure(&ticker),
+                         };
+                         if failures <= 1 {
+                             cloned_state.record_trade(JournalEntry {
+                                 timestamp: now_millis(),
+                                 market: ticker,
+                                 side: OrderSide::Ask,
+                                 amount: 100.0,
+                                 reference_price,
+                                 paper: cloned_executor.is_paper(),
+                                 error: result.as_ref().err().cloned(),
+                                 reason: Some("kill-switch".to_string()),
+                                 fill: result.ok().flatten(),
+                                 identifier: Some(identifier),
+                             });
+                         }
+                     });
+                 }
+                 continue;
+             }
+
+             // 보유 종목의 청산 규칙은 일시정지 여부나 매수 스캔과 관계없이 매 틱 확인합니다.
+             // 환산 비율은 따로 조회하며, 실패하면 원화 마켓 보유 종목만 확인합니다. 잔고를 조회하지 못하면 이번 틱은 건너뜁니다.
+             let rates = try_fetch_quote_rates().await.unwrap_or_default();
+             let Ok(all_positions) = executor.positions().await else {
+                 continue;
+             };
+             let positions = positions_in_quote(all_positions.clone(), quote, &rates);
+             let managed = positions
                  .iter()
-                 .for_each(
-                     |data| {
-                         if data.check_rsi_divergence(&RsiDivergenceCheckMode::Minpoint, &30.0, &5) // 5개 데이터 이내 RSI 다이버전스 발생
-                             && data.get_last_price() < data.get_ewm_mean() // 현재 가격이 평균보다 낮음
-                         {
-                             let ticker = data[0].market.clone();
-                             let cloned_account = upbit_account.clone();
-                             task::spawn(async move {
-                                 if let Some(krw) = get_balance_of(&cloned_account, "KRW").await {
-                                     if let None = get_balance_of(&cloned_account, &ticker).await {
-                                         buy_market_order(&cloned_account, &ticker, krw * 0.2).await;
-                                     }
-                                 }
+                 .filter(|position| !state.is_unmanaged(&position.market))
+                 .cloned()
+                 .collect::<Vec<_>>();
+             state.tracker.sync(&managed, &state.journal);
+             // 보유 종목 캔들은 매수 스캔과 따로 조회하며, 캔들을 받지 못한 종목은 현재가만으로 판단합니다.
+             let held_markets = managed.iter().map(|position| position.market.clone()).collect::<Vec<String>>();
+             let held = async {
+                 let report = scan_candles(held_markets.clone(), CandleUnit::Min1, 200, &config.scan).await;
+                 let missing = report.failed.clone();
+                 let snapshots = if missing.is_empty() { Vec::new() } else { get_tickers(&missing).await.unwrap_or_default() };
+                 (report, snapshots)
+             };
+             let (held_report, held_snapshots) = tokio::select! {
+                 fetched = held => fetched,
+                 _ = shutdown.changed() => break,
+             };
+             for position in &managed {
+                 let data = held_report.candle_datas.iter().find(|data| !data.is_empty() && data[0].market == position.market);
+                 let snapshot_price = held_snapshots.iter().find(|snapshot| snapshot.market == position.market).map(|snapshot| snapshot.trade_price);
+                 let Some(reference_price) = data.map(|data| data[0].trade_price).or(snapshot_price) else {
+                     continue;
+                 };
+                 let indicator = data.and_then(|data| config.exits.trailing_indicator(data));
+                 let reason = match state.tracker.evaluate(&config.exits, &position.market, reference_price, indicator) {
+                     Some(reason) => reason,
+                     // 청산 규칙에 걸리지 않았더라도 매도 체결이 우세하면 매도합니다.
+                     None if config.flow.sell_delta_ratio.is_some() => match get_recent_trade_ticks(&position.market, config.flow.ticks).await {
+                         Ok(ticks) if is_sell_flow(&TradeFlow::from_ticks(&ticks, config.flow.large_trade_multiple), &config.flow) => ExitReason::SellingPressure,
+                         _ => continue,
+                     },
+                     None => continue,
+                 };
+
+                 let ticker = position.market.clone();
+                 // 캔들이 없으면 분 단위 시각으로 식별자를 만들어 같은 분의 중복 청산을 막습니다.
+                 let candle_time = data.map_or_else(|| (now_millis() / 60_000).to_string(), |data| data[0].candle_date_time_utc.clone());
+                 let identifier = order_identifier(&ticker, OrderSide::Ask, &candle_time);
+                 let Some(guard) = state.orders.try_lock(&ticker, OrderSide::Ask, &identifier) else {
+                     continue;
+                 };
+                 let cloned_executor = executor.clone();
+                 let cloned_state = Arc::clone(&state);
+                 order_tasks.spawn(async move {
+                     let _guard = guard;
+                     let result = cloned_executor.sell_market_order(&ticker, 100.0, Some(&identifier)).await;
+                     cloned_state.record_trade(JournalEntry {
+                         timestamp: now_millis(),
+                         market: ticker,
+                         side: OrderSide::Ask,
+                         amount: 100.0,
+                         reference_price,
+                         paper: cloned_executor.is_paper(),
+                         error: result.as_ref().err().cloned(),
+                         reason: Some(reason.as_str().to_string()),
+                         fill: result.ok().flatten(),
+                         identifier: Some(identifier),
+                     });
+                 });
+             }
+
+             if state.is_paused() {
+                 continue;
+             }
+
+             if config.markets.exclude_suspended_wallets && wallets_checked_at.is_none_or(|at| at.elapsed() >= WALLET_STATUS_INTERVAL) {
+                 if let Some(account) = &wallet_account {
+                     if let Ok(statuses) = get_wallet_status(account).await {
+                         suspended_wallets = suspended_currencies(&statuses);
+                         wallets_checked_at = Some(time::Instant::now());
+                     }
+                 }
+             }
+
+             // 스캔 도중 종료가 요청되면 스캔 결과를 버리고 바로 종료합니다.
+             // 마켓 목록을 받지 못하면 이번 스캔에서는 매수 대상이 없습니다.
+             let scan = async {
+                 let markets = get_all_markets().await.unwrap_or_default();
+                 // 현재가는 여러 종목을 한 번에 조회하며, 실패하면 캔들의 종가를 사용합니다.
+                 let snapshots = get_tickers(&config.markets.quote_markets(&markets)).await.unwrap_or_default();
+                 let selected = config.markets.without_suspended(config.markets.select(&markets, &snapshots), &suspended_wallets);
+                 // 보유 종목은 위에서 이미 캔들을 받았으므로 매수 대상만 스캔합니다.
+                 let scan_markets = selected
+                     .iter()
+                     .filter(|market| !held_markets.contains(market))
+                     .cloned()
+                     .collect::<Vec<String>>();
+                 let scan_report = scan_candles(scan_markets, CandleUnit::Min1, 200, &config.scan).await;
+                 let listed = markets.into_iter().map(|ticker| ticker.market).collect::<HashSet<String>>();
+                 (scan_report, snapshots, selected.into_iter().collect::<HashSet<String>>(), listed)
+             };
+             let (scan_report, snapshots, selected, listed) = tokio::select! {
+                 scanned = scan => scanned,
+                 _ = shutdown.changed() => break,
+             };
+             scans += 1;
+             let failed = scan_report.failed.len() + held_report.failed.len();
+             // 보유 종목의 캔들도 매도 조건 확인에 함께 사용합니다.
+             let mut candle_datas = scan_report.candle_datas;
+             candle_datas.extend(held_report.candle_datas);
+             state.mark_scanned(candle_datas.len(), failed);
+
+             let mut prices = candle_datas
+                 .iter()
+                 .filter(|data| !data.is_empty())
+                 .map(|data| (data[0].market.clone(), data[0].trade_price))
+                 .collect::<HashMap<String, f64>>();
+             prices.extend(held_snapshots.into_iter().map(|snapshot| (snapshot.market, snapshot.trade_price)));
+             prices.extend(snapshots.into_iter().map(|snapshot| (snapshot.market, snapshot.trade_price)));
+             // 평가에 필요한데 스캔하지 않은 보유 마켓의 현재가는 따로 조회합니다.
+             // 상장 폐지 등으로 마켓 목록에 없는 종목이 섞이면 요청 전체가 거부되므로 제외합니다.
+             let missing = all_positions
+                 .iter()
+                 .map(|position| position.market.clone())
+                 .filter(|market| listed.contains(market) && !prices.contains_key(market))
+                 .collect::<Vec<String>>();
+             if !missing.is_empty() {
+                 if let Ok(snapshots) = get_tickers(&missing).await {
+                     prices.extend(snapshots.into_iter().map(|snapshot| (snapshot.market, snapshot.trade_price)));
+                 }
+             }
+
+             // 시세 API가 차단되었거나, 절반 넘는 종목의 조회가 실패했거나, 호가 통화를 원화로 환산할 수 없으면 시장 데이터를 믿을 수 없습니다.
+             let degraded = breaker_of(RateGroup::Quotation).state() != CircuitState::Closed
+                 || failed * 2 > candle_datas.len() + failed
+                 || rates.rate(quote).is_none();
+             state.set_degraded(degraded);
+
+             // 원화 잔고를 모른 채 평가하면 손실로 보여 킬 스위치가 켜질 수 있으므로 이번 틱은 건너뜁니다.
+             let Ok(krw) = executor.balance_of("KRW").await.map(|krw| krw.unwrap_or(0.0)) else {
+                 continue;
+             };
+             let portfolio = valuate(krw, &all_positions, &prices, &state.journal.entries(), &rates);
+             state.record_equity(&portfolio);
+             // 손실 한도는 원화 평가액으로, 매수 금액은 호가 통화로 환산한 평가액으로 판단합니다.
+             let daily_pnl = state.risk.update_equity(portfolio.total_equity);
+             let equity = rates.convert(portfolio.total_equity, QuoteCurrency::Krw, quote).unwrap_or(0.0);
+             if config.risk.kill_switch_loss.is_some_and(|limit| -daily_pnl >= limit) {
+                 state.record_risk_block("*", &RiskBlock::DailyLossLimit(-daily_pnl));
+                 state.set_killed(true);
+                 continue;
+             }
+
+             if state.is_shutting_down() {
+                 break;
+             }
+
+             let mut exposure = Exposure::new(&positions, &prices);
+
+             //구매 점수 높은 종목을 찾아 구매 시행
+             let candidates = rank_buy_candidates(&candle_datas, &config.scoring.weights);
+             state.set_candidates(candidates.iter().map(|(_, score)| score.clone()).collect());
+             if degraded && !candidates.is_empty() {
+                 state.record_risk_block("*", &RiskBlock::StaleMarketData);
+             }
+             let mut cash = executor.balance_of(quote.code()).await.ok().flatten().unwrap_or(0.0);
+             let mut bought = 0;
+             for (data, _) in candidates {
+                 if degraded || bought >= config.scoring.top_n || cash < quote.min_order() {
+                     break;
+                 }
+                 let ticker = data[0].market.clone();
+                 // 마켓 필터를 통과하지 못한 종목은 보유 종목이라 스캔했더라도 매수하지 않습니다.
+                 if exposure.contains(&ticker) || !selected.contains(&ticker) {
+                     continue;
+                 }
+                 if !is_buy_confirmed(data, &config.confirmations) {
+                     continue;
+                 }
+                 let budget = config.sizing.position_size(cash, equity, data);
+                 if budget < quote.min_order() {
+                     continue;
+                 }
+                 if let Err(block) = state.risk.check_entry(&config.risk, &exposure, &ticker, budget) {
+                     state.record_risk_block(&ticker, &block);
+                     continue;
+                 }
+                 // 체결 흐름 조건이 있으면 최근 체결에서 매수세를 확인합니다.
+                 if config.flow.min_buy_delta_ratio.is_some() {
+                     let Ok(ticks) = get_recent_trade_ticks(&ticker, config.flow.ticks).await else {
+                         state.record_risk_block(&ticker, &RiskBlock::StaleMarketData);
+                         continue;
+                     };
+                     if !is_buy_flow(&TradeFlow::from_ticks(&ticks, config.flow.large_trade_multiple), &config.flow) {
+                         continue;
+                     }
+                 }
+                 // 슬리피지 한도가 있으면 호가 잔량으로 체결 가격을 추정해 봅니다.
+                 if config.risk.max_slippage.is_some() {
+                     let checked = match get_orderbook(&ticker).await {
+                         Ok(orderbook) => config.risk.check_liquidity(&orderbook, budget),
+                         Err(_) => Err(RiskBlock::StaleMarketData),
+                     };
+                     if let Err(block) = checked {
+                         state.record_risk_block(&ticker, &block);
+                         continue;
+                     }
+                 }
+                 let reference_price = data[0].trade_price;
+                 let identifier = order_identifier(&ticker, OrderSide::Bid, &data[0].candle_date_time_utc);
+                 // 이미 주문이 진행 중인 종목은 건너뜁니다.
+                 let Some(guard) = state.orders.try_lock(&ticker, OrderSide::Bid, &identifier) else {
+                     continue;
+                 };
+                 if let Err(block) = state.risk.try_record_order(&config.risk) {
+                     state.record_risk_block(&ticker, &block);
+                     continue;
+                 }
+                 exposure.add(&ticker, budget);
+                 cash -= budget;
+                 bought += 1;
+                 let cloned_executor = executor.clone();
+                 let cloned_state = Arc::clone(&state);
+                 order_tasks.spawn(async move {
+                     let _guard = guard;
+                     if let Ok(Some(cash)) = cloned_executor.balance_of(quote.code()).await {
+                         if let Ok(None) = cloned_executor.balance_of(&ticker).await {
+                             let budget = budget.min(cash);
+                             let result = cloned_executor.buy_market_order(&ticker, budget, Some(&identifier)).await;
+                             cloned_state.record_trade(JournalEntry {
+                                 timestamp: now_millis(),
+                                 market: ticker,
+                                 side: OrderSide::Bid,
+                                 amount: budget,
+                                 reference_price,
+                                 paper: cloned_executor.is_paper(),
+                                 error: result.as_ref().err().cloned(),
+                                 reason: None,
+                                 fill: result.ok().flatten(),
+                                 identifier: Some(identifier),
                              });
                          }
                      }
-                 );
+                 });
+             }
 
              // 판매 점수 높은 종목을 찾아 판매 시행
              candle_datas
                  .iter()
                  .for_each(
                      |data| {
-                         if data.check_rsi_divergence(&RsiDivergenceCheckMode::Peak, &70.0, &5) // 5개 데이터 이내 RSI 다이버전스 발생
-                             || data.check_rsi_breaking_peak(&4, &70.0) // RSI 꺾임 발생
-                             || data.get_rsi() > 60.0 && data.get_last_price() < data.get_ewm_mean() // RSI가 올랐는데도 가격이 오르지 않았으면 가망이 없는 종목이라 판단
-                         {
+                         if is_sell_signal(data) && !state.is_unmanaged(&data[0].market) {
                              let ticker = data[0].market.clone();
-                             let cloned_account = upbit_account.clone();
+                             let reference_price = data[0].trade_price;
+                             let identifier = order_identifier(&ticker, OrderSide::Ask, &data[0].candle_date_time_utc);
+                             let Some(guard) = state.orders.try_lock(&ticker, OrderSide::Ask, &identifier) else {
+                                 return;
+                             };
+                             let cloned_executor = executor.clone();
+                             let cloned_state = Arc::clone(&state);
 
-                             task::spawn(async move {
-                                 if let Some(_) = get_balance_of(&cloned_account, &ticker).await {
-                                     let _ = sell_market_order(&cloned_account, &ticker, 100.0).await;
+                             order_tasks.spawn(async move {
+                                 let _guard = guard;
+                                 if let Ok(Some(_)) = cloned_executor.balance_of(&ticker).await {
+                                     let result = cloned_executor.sell_market_order(&ticker, 100.0, Some(&identifier)).await;
+                                     cloned_state.record_trade(JournalEntry {
+                                         timestamp: now_millis(),
+                                         market: ticker,
+                                         side: OrderSide::Ask,
+                                         amount: 100.0,
+                                         reference_price,
+                                         paper: cloned_executor.is_paper(),
+                                         error: result.as_ref().err().cloned(),
+                                         reason: None,
+                                         fill: result.ok().flatten(),
+                                         identifier: Some(identifier),
+                                     });
                                  }
                              });
                          }
                      }
                  );
          }
+
+        let (drained_tasks, abandoned_tasks) = drain_order_tasks(&mut order_tasks, config.drain_timeout).await;
+        // 주문 작업이 모두 끝난 뒤에 미체결 주문을 취소해야 방금 낸 주문도 함께 취소됩니다.
+        let cancelled_orders = match &executor {
+            OrderExecutor::Live(account) if config.cancel_open_orders => cancel_open_limit_orders(account).await,
+            _ => 0,
+        };
+        ShutdownSummary {
+            scans,
+            orders_recorded: state.journal.len() - journal_len_at_start,
+            drained_tasks,
+            abandoned_tasks,
+            cancelled_orders,
+        }
     })
 }
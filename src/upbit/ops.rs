@@ -1,7 +1,8 @@
 use std::ops::{BitAnd, BitOr};
 use polars::series::ops::NullBehavior;
 use polars::prelude::*;
-use crate::upbit::{CandleData, CandleDataOperation};
+use serde::Serialize;
+use crate::upbit::response::{CandleData, CandleDataOperation};
 
 pub fn get_rsi(candle_data: &[CandleData]) -> f64 {
     let rsi_series = get_rsi_series(candle_data);
@@ -52,7 +53,16 @@ pub fn get_rsi_series(candle_data: &[CandleData]) -> Series {
 }
 
 pub fn get_ewm_mean(candle_data: &[CandleData]) -> f64 {
-    let ewm_mean_df = candle_data
+    let ewm_mean_series = get_ewm_mean_series(candle_data);
+
+    return match ewm_mean_series.get(ewm_mean_series.len()-1).unwrap() {
+        AnyValue::Float64(f) => f,
+        _ => panic!("형식이 잘못되었습니다.")
+    };
+}
+
+pub fn get_ewm_mean_series(candle_data: &[CandleData]) -> Series {
+    candle_data
         .as_dataframe()
         .lazy()
         .select([
@@ -60,12 +70,10 @@ pub fn get_ewm_mean(candle_data: &[CandleData]) -> f64 {
                 .ewm_mean(EWMOptions::default().and_com(13.0).and_min_periods(14))
                 .alias("ewm_mean")
         ])
-        .collect().unwrap();
-
-    return match ewm_mean_df.get_columns()[0].get(ewm_mean_df.get_columns()[0].len()-1).unwrap() {
-        AnyValue::Float64(f) => f,
-        _ => panic!("형식이 잘못되었습니다.")
-    };
+        .collect().unwrap()
+        .column("ewm_mean")
+        .unwrap()
+        .clone()
 }
 
 pub fn get_std(candle_data: &[CandleData]) -> f64 {
@@ -86,6 +94,57 @@ pub fn get_std(candle_data: &[CandleData]) -> f64 {
     }
 }
 
+// 시간 순 DataFrame의 f64 열을 목록으로 꺼냅니다.
+fn column_values(candle_df: &DataFrame, name: &str) -> Vec<f64> {
+    candle_df.column(name).unwrap().f64().unwrap().into_no_null_iter().collect()
+}
+
+// 와일더 평활, 기간이 period인 지수 이동 평균으로 alpha가 1 / period입니다.
+fn wilder_options(period: usize) -> EWMOptions {
+    EWMOptions { alpha: 1.0 / period as f64, ..EWMOptions::default() }.and_min_periods(period)
+}
+
+/// # True Range
+/// 고가 - 저가, |고가 - 직전 종가|, |저가 - 직전 종가| 중 가장 큰 값이며, 첫 캔들은 고가 - 저가입니다.
+pub fn get_true_range_series(candle_data: &[CandleData]) -> Series {
+    let candle_df = candle_data.as_dataframe();
+    let (highs, lows, closes) = (column_values(&candle_df, "high"), column_values(&candle_df, "low"), column_values(&candle_df, "price"));
+    let true_ranges = (0..closes.len())
+        .map(|i| {
+            let range = highs[i] - lows[i];
+            if i == 0 {
+                range
+            } else {
+                range
+                    .max((highs[i] - closes[i-1]).abs())
+                    .max((lows[i] - closes[i-1]).abs())
+            }
+        })
+        .collect::<Vec<f64>>();
+    Series::from_vec("tr", true_ranges)
+}
+
+/// # ATR (Average True Range)
+/// true range를 period 기준 와일더 평활하여 시간 순 Series로 반환합니다.
+pub fn get_atr_series(candle_data: &[CandleData], period: usize) -> Series {
+    DataFrame::new(vec![get_true_range_series(candle_data)]).unwrap()
+        .lazy()
+        .select([
+            col("tr")
+                .ewm_mean(wilder_options(period))
+                .alias("atr")
+        ])
+        .collect().unwrap()
+        .column("atr")
+        .unwrap()
+        .clone()
+}
+
+/// 가장 최근 ATR, 캔들이 period개보다 적어 ATR을 구할 수 없으면 None을 반환합니다.
+pub fn get_atr(candle_data: &[CandleData], period: usize) -> Option<f64> {
+    latest_value(&get_atr_series(candle_data, period))
+}
+
 pub enum RsiDivergenceCheckMode {
     Peak,
     Minpoint,
@@ -93,11 +152,25 @@ pub enum RsiDivergenceCheckMode {
 
 /// # RSI 다이버전스 발생 확인
 /// 주어진 Candle Data 슬라이스 내에서 RSI 다이버전스가 발생했으면 true를 반환합니다.
+pub fn check_rsi_divergence(candle_data: &[CandleData], divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> bool {
+    get_rsi_divergence(candle_data, divergence_check_mode, rsi_bound, recent_data_bound).is_some()
+}
+
+/// # RSI 다이버전스
+/// 다이버전스를 이루는 가장 최근 두 고점(저점) 사이의 RSI 변화량과 가격 변화율(%)입니다.
+#[derive(Debug, Clone, Copy)]
+pub struct RsiDivergence {
+    pub rsi_delta: f64,
+    pub price_delta: f64,
+}
+
+/// # RSI 다이버전스 찾기
+/// check_rsi_divergence와 같은 기준으로 판단하며, 다이버전스가 발생했으면 그 크기를 반환합니다.
 /// ### divergence_check_mode가 Peak인 경우
-/// 가격이 상승세인 상황에서, bound를 넘는 RSI 고점 가장 최근 둘에 대해 RSI 고점은 하락, 가격 고점은 상승하는 경우 true를 반환합니다.
+/// 가격이 상승세인 상황에서, bound를 넘는 RSI 고점 가장 최근 둘에 대해 RSI 고점은 하락, 가격 고점은 상승하는 경우 다이버전스로 판단합니다.
 /// ### divergence_check_mode가 Minpoint인 경우
-/// 가격이 하락세인 상황에서, bound 미만인 RSI 고점 가장 최근 둘에 대해 RSI 저점은 상승, 가격 저점은 하락하는 경우 true를 반환합니다.
-pub fn check_rsi_divergence(candle_data: &[CandleData], divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> bool {
+/// 가격이 하락세인 상황에서, bound 미만인 RSI 고점 가장 최근 둘에 대해 RSI 저점은 상승, 가격 저점은 하락하는 경우 다이버전스로 판단합니다.
+pub fn get_rsi_divergence(candle_data: &[CandleData], divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> Option<RsiDivergence> {
     let rsi_series = get_rsi_series(candle_data);
 
     match divergence_check_mode {
@@ -154,24 +227,24 @@ pub fn check_rsi_divergence(candle_data: &[CandleData], divergence_check_mode: &
                         _ => panic!("잘못된 형식입니다.")
                     };
 
-                    // 우측 저점이 최근 데이터 판정 기준보다 오래된 경우 false를 반환합니다.
-                    if right_index < (rsi_series.len()-recent_data_bound) { return false; }
-                    // 유효한 다이버전스 상황이 아니라면 false를 반환합니다.
-                    if left_rsi < right_rsi || left_price > right_price { return false; }
+                    // 우측 저점이 최근 데이터 판정 기준보다 오래된 경우 None을 반환합니다.
+                    if right_index < (rsi_series.len()-recent_data_bound) { return None; }
+                    // 유효한 다이버전스 상황이 아니라면 None을 반환합니다.
+                    if left_rsi < right_rsi || left_price > right_price { return None; }
 
                     // 다이버전스 상황 자체는 유효하지만, 추세의 반전을 의미하는지 확인합니다.
                     let past_df = candle_df.slice(0, left_index);
-                    let past_price_delta_mean = match past_df.column("price").unwrap()
+                    let past_price_delta_mean = past_df.column("price").unwrap()
                         .diff(1, NullBehavior::Ignore).unwrap()
-                        .mean() {
-                        Some(f) => f,
-                        None => return false
-                    };
+                        .mean()?;
 
-                    if past_price_delta_mean < 0.0 { return false; }
+                    if past_price_delta_mean < 0.0 { return None; }
 
                     // 가격은 상승중이며 유효한 다이버전스가 발생하였습니다.
-                    return true;
+                    return Some(RsiDivergence {
+                        rsi_delta: (right_rsi - left_rsi).abs(),
+                        price_delta: ((right_price / left_price - 1.0) * 100.0).abs(),
+                    });
                 }
             }
         }
@@ -228,30 +301,30 @@ pub fn check_rsi_divergence(candle_data: &[CandleData], divergence_check_mode: &
                         _ => panic!("잘못된 형식입니다.")
                     };
 
-                    // 우측 저점이 최근 데이터 판정 기준보다 오래된 경우 false를 반환합니다.
-                    if right_index < (rsi_series.len()-recent_data_bound) { return false; }
-                    // 유효한 다이버전스 상황이 아니라면 false를 반환합니다.
-                    if left_rsi > right_rsi || left_price < right_price { return false; }
+                    // 우측 저점이 최근 데이터 판정 기준보다 오래된 경우 None을 반환합니다.
+                    if right_index < (rsi_series.len()-recent_data_bound) { return None; }
+                    // 유효한 다이버전스 상황이 아니라면 None을 반환합니다.
+                    if left_rsi > right_rsi || left_price < right_price { return None; }
 
                     // 다이버전스 상황 자체는 유효하지만, 추세의 반전을 의미하는지 확인합니다.
                     let past_df = candle_df.slice(0, left_index);
-                    let past_price_delta_mean = match past_df.column("price").unwrap()
+                    let past_price_delta_mean = past_df.column("price").unwrap()
                         .diff(1, NullBehavior::Ignore).unwrap()
-                        .mean() {
-                        Some(f) => f,
-                        None => return false
-                    };
+                        .mean()?;
 
-                    if past_price_delta_mean > 0.0 { return false; }
+                    if past_price_delta_mean > 0.0 { return None; }
 
                     // 가격은 하락중이며 유효한 다이버전스가 발생하였습니다.
-                    return true;
+                    return Some(RsiDivergence {
+                        rsi_delta: (right_rsi - left_rsi).abs(),
+                        price_delta: ((right_price / left_price - 1.0) * 100.0).abs(),
+                    });
                 }
             }
         }
     }
 
-    false
+    None
 }
 /// # RSI 꺾임 확인
 /// 다음 값이 감소하며 rsi_bound를 넘는 RSI 지점을 검색합니다.
@@ -277,4 +350,839 @@ pub fn check_rsi_breaking_peak(candle_data: &[CandleData], count: &usize, rsi_bo
         )
         .slice((candle_data.len()-count) as i64, count-1)
         .all()
-}
\ No newline at end of file
+}
+
+/// # MACD 기간
+/// fast, slow는 MACD 선을 만드는 두 지수 이동 평균의 기간이고, signal은 시그널 선의 기간입니다.
+#[derive(Debug, Clone, Copy)]
+pub struct MacdOptions {
+    pub fast: usize,
+    pub slow: usize,
+    pub signal: usize,
+}
+
+impl Default for MacdOptions {
+    fn default() -> Self {
+        MacdOptions { fast: 12, slow: 26, signal: 9 }
+    }
+}
+
+/// # MACD
+/// 시간 순 Series이며, 기간이 채워지지 않은 앞부분은 null입니다.
+/// - line: 빠른 EMA - 느린 EMA
+/// - signal: line의 EMA
+/// - histogram: line - signal
+#[derive(Debug, Clone)]
+pub struct Macd {
+    pub line: Series,
+    pub signal: Series,
+    pub histogram: Series,
+}
+
+/// 한 시점의 MACD 값
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct MacdValue {
+    pub line: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// MACD 선과 시그널 선의 교차, Golden은 MACD 선이 시그널 선을 위로 뚫은 경우입니다.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MacdCross {
+    Golden,
+    Dead,
+}
+
+/// 다이버전스를 확인할 MACD 값
+#[derive(Debug, Clone, Copy)]
+pub enum MacdComponent {
+    Line,
+    Histogram,
+}
+
+/// # MACD 다이버전스
+/// 다이버전스를 이루는 가장 최근 두 고점(저점) 사이의 MACD 변화량과 가격 변화율(%)입니다.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct MacdDivergence {
+    pub macd_delta: f64,
+    pub price_delta: f64,
+}
+
+impl Macd {
+    /// 가장 최근 MACD 값, 기간이 채워지지 않았으면 None입니다.
+    pub fn latest(&self) -> Option<MacdValue> {
+        let last = self.line.len().checked_sub(1)?;
+        Some(MacdValue {
+            line: self.line.f64().unwrap().get(last)?,
+            signal: self.signal.f64().unwrap().get(last)?,
+            histogram: self.histogram.f64().unwrap().get(last)?,
+        })
+    }
+
+    /// # MACD 교차 확인
+    /// 가장 최근 recent_data_bound개 데이터 안에서 일어난 마지막 교차를 반환합니다.
+    pub fn last_cross(&self, recent_data_bound: usize) -> Option<MacdCross> {
+        let histogram = series_values(&self.histogram);
+        let from = histogram.len().saturating_sub(recent_data_bound).max(1);
+        (from..histogram.len())
+            .rev()
+            .find_map(|i| match (histogram[i - 1], histogram[i]) {
+                (Some(previous), Some(current)) if previous <= 0.0 && current > 0.0 => Some(MacdCross::Golden),
+                (Some(previous), Some(current)) if previous >= 0.0 && current < 0.0 => Some(MacdCross::Dead),
+                _ => None,
+            })
+    }
+}
+
+// 지수 이동 평균 기간을 EWMOptions로 바꿉니다. MACD는 보정 없는 EMA를 사용합니다.
+fn ema_options(period: usize) -> EWMOptions {
+    EWMOptions::default().and_span(period).and_adjust(false).and_min_periods(period)
+}
+
+// null은 None으로 두고 Series를 f64 목록으로 바꿉니다.
+fn series_values(series: &Series) -> Vec<Option<f64>> {
+    series.f64().unwrap().into_iter().collect()
+}
+
+/// 시간 순 Series의 마지막 값, 비어 있거나 null이면 None입니다.
+pub fn latest_value(series: &Series) -> Option<f64> {
+    series.f64().unwrap().into_iter().last()?
+}
+
+pub fn get_macd(candle_data: &[CandleData], options: &MacdOptions) -> Macd {
+    let macd_df = candle_data
+        .as_dataframe()
+        .lazy()
+        .select([
+            (col("price").ewm_mean(ema_options(options.fast)) - col("price").ewm_mean(ema_options(options.slow)))
+                .alias("line"),
+        ])
+        .collect().unwrap()
+        .lazy()
+        .with_column(col("line").ewm_mean(ema_options(options.signal)).alias("signal"))
+        .with_column((col("line") - col("signal")).alias("histogram"))
+        .collect().unwrap();
+
+    Macd {
+        line: macd_df.column("line").unwrap().clone(),
+        signal: macd_df.column("signal").unwrap().clone(),
+        histogram: macd_df.column("histogram").unwrap().clone(),
+    }
+}
+
+/// # MACD 다이버전스 발생 확인
+/// 주어진 Candle Data 슬라이스 내에서 MACD 다이버전스가 발생했으면 true를 반환합니다.
+pub fn check_macd_divergence(candle_data: &[CandleData], options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> bool {
+    get_macd_divergence(candle_data, options, component, divergence_check_mode, recent_data_bound).is_some()
+}
+
+/// # MACD 다이버전스 찾기
+/// get_rsi_divergence와 같은 방식으로 판단하며, RSI 경계 대신 0을 기준으로 합니다.
+/// ### divergence_check_mode가 Peak인 경우
+/// 가격이 상승세인 상황에서, 0보다 큰 MACD 고점 가장 최근 둘에 대해 MACD 고점은 하락, 가격 고점은 상승하는 경우 다이버전스로 판단합니다.
+/// ### divergence_check_mode가 Minpoint인 경우
+/// 가격이 하락세인 상황에서, 0보다 작은 MACD 저점 가장 최근 둘에 대해 MACD 저점은 상승, 가격 저점은 하락하는 경우 다이버전스로 판단합니다.
+pub fn get_macd_divergence(candle_data: &[CandleData], options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> Option<MacdDivergence> {
+    let macd = get_macd(candle_data, options);
+    let values = series_values(match component {
+        MacdComponent::Line => &macd.line,
+        MacdComponent::Histogram => &macd.histogram,
+    });
+    let prices = series_values(candle_data.as_dataframe().column("price").unwrap())
+        .into_iter()
+        .map(|price| price.unwrap())
+        .collect::<Vec<f64>>();
+
+    // 양옆보다 크고 0보다 큰 고점, 또는 양옆보다 작고 0보다 작은 저점
+    let mut extremum_indexes = (1..values.len().saturating_sub(1))
+        .filter(|&i| match (values[i - 1], values[i], values[i + 1]) {
+            (Some(left), Some(value), Some(right)) => match divergence_check_mode {
+                RsiDivergenceCheckMode::Peak => value > left && value > right && value > 0.0,
+                RsiDivergenceCheckMode::Minpoint => value < left && value < right && value < 0.0,
+            },
+            _ => false,
+        })
+        .collect::<Vec<usize>>();
+
+    let right_index = extremum_indexes.pop()?;
+    let left_index = extremum_indexes.pop()?;
+
+    // 우측 극점이 최근 데이터 판정 기준보다 오래된 경우 None을 반환합니다.
+    if right_index < values.len().saturating_sub(*recent_data_bound) { return None; }
+
+    let (left_macd, right_macd) = (values[left_index].unwrap(), values[right_index].unwrap());
+    let (left_price, right_price) = (prices[left_index], prices[right_index]);
+    // 추세의 반전을 의미하는지 확인하기 위한 왼쪽 극점 이전의 평균 가격 변화
+    let past_price_delta_mean = prices[..=left_index]
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .sum::<f64>() / left_index.max(1) as f64;
+
+    let is_divergence = match divergence_check_mode {
+        RsiDivergenceCheckMode::Peak => left_macd >= right_macd && left_price <= right_price && past_price_delta_mean >= 0.0,
+        RsiDivergenceCheckMode::Minpoint => left_macd <= right_macd && left_price >= right_price && past_price_delta_mean <= 0.0,
+    };
+    if !is_divergence { return None; }
+
+    Some(MacdDivergence {
+        macd_delta: (right_macd - left_macd).abs(),
+        price_delta: ((right_price / left_price - 1.0) * 100.0).abs(),
+    })
+}
+
+/// # 밴드 설정
+/// - period: 이동 평균, 표준편차, ATR 기간
+/// - std_multiple: 볼린저 밴드 폭 (표준편차의 배수)
+/// - atr_multiple: 켈트너 채널 폭 (ATR의 배수)
+#[derive(Debug, Clone, Copy)]
+pub struct BandOptions {
+    pub period: usize,
+    pub std_multiple: f64,
+    pub atr_multiple: f64,
+}
+
+impl Default for BandOptions {
+    fn default() -> Self {
+        BandOptions { period: 20, std_multiple: 2.0, atr_multiple: 1.5 }
+    }
+}
+
+/// # 밴드
+/// 시간 순 Series이며, 기간이 채워지지 않은 앞부분은 null입니다.
+#[derive(Debug, Clone)]
+pub struct Bands {
+    pub upper: Series,
+    pub middle: Series,
+    pub lower: Series,
+}
+
+/// 한 시점의 밴드 값
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct BandValue {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+impl Bands {
+    /// 가장 최근 밴드 값, 기간이 채워지지 않았으면 None입니다.
+    pub fn latest(&self) -> Option<BandValue> {
+        let last = self.middle.len().checked_sub(1)?;
+        Some(BandValue {
+            upper: self.upper.f64().unwrap().get(last)?,
+            middle: self.middle.f64().unwrap().get(last)?,
+            lower: self.lower.f64().unwrap().get(last)?,
+        })
+    }
+}
+
+impl BandValue {
+    /// %B, 가격이 하단이면 0, 상단이면 1입니다.
+    pub fn percent_b(&self, price: f64) -> f64 {
+        (price - self.lower) / (self.upper - self.lower)
+    }
+
+    /// 중간선 대비 밴드 폭
+    pub fn bandwidth(&self) -> f64 {
+        (self.upper - self.lower) / self.middle
+    }
+}
+
+/// # 볼린저 밴드
+/// period 단순 이동 평균 ± std_multiple × period 표준편차입니다.
+/// 표준편차는 get_std와 같은 모표준편차(ddof 0)입니다. polars의 rolling_std는 표본표준편차(ddof 1)만 계산하므로 √((n-1)/n)을 곱해 맞춥니다.
+pub fn get_bollinger_bands(candle_data: &[CandleData], options: &BandOptions) -> Bands {
+    let rolling = RollingOptions {
+        window_size: Duration::new(options.period as i64),
+        min_periods: options.period,
+        ..RollingOptions::default()
+    };
+    let population = ((options.period as f64 - 1.0) / options.period as f64).sqrt();
+    let bands = candle_data
+        .as_dataframe()
+        .lazy()
+        .select([
+            col("price").rolling_mean(rolling.clone()).alias("middle"),
+            (col("price").rolling_std(rolling) * lit(population)).alias("std"),
+        ])
+        .collect().unwrap();
+    let middle = bands.column("middle").unwrap().clone();
+    let std = bands.column("std").unwrap();
+
+    Bands {
+        upper: (&middle + &(std * options.std_multiple)).rename("upper").clone(),
+        lower: (&middle - &(std * options.std_multiple)).rename("lower").clone(),
+        middle,
+    }
+}
+
+/// # 켈트너 채널
+/// period 지수 이동 평균 ± atr_multiple × period ATR입니다.
+pub fn get_keltner_channels(candle_data: &[CandleData], options: &BandOptions) -> Bands {
+    let middle = candle_data
+        .as_dataframe()
+        .lazy()
+        .select([col("price").ewm_mean(ema_options(options.period)).alias("middle")])
+        .collect().unwrap()
+        .column("middle").unwrap()
+        .clone();
+    let atr = get_atr_series(candle_data, options.period);
+
+    Bands {
+        upper: (&middle + &(&atr * options.atr_multiple)).rename("upper").clone(),
+        lower: (&middle - &(&atr * options.atr_multiple)).rename("lower").clone(),
+        middle,
+    }
+}
+
+/// # 볼린저 %B
+/// 가장 최근 가격의 볼린저 밴드 안 위치입니다. 기간이 채워지지 않았으면 None입니다.
+pub fn get_percent_b(candle_data: &[CandleData], options: &BandOptions) -> Option<f64> {
+    let band = get_bollinger_bands(candle_data, options).latest()?;
+    Some(band.percent_b(candle_data.first()?.trade_price))
+}
+
+/// # 볼린저 밴드 폭
+pub fn get_bandwidth(candle_data: &[CandleData], options: &BandOptions) -> Option<f64> {
+    Some(get_bollinger_bands(candle_data, options).latest()?.bandwidth())
+}
+
+/// # 하단 밴드 이탈 확인
+/// 가장 최근 가격이 볼린저 밴드 하단보다 낮으면 true를 반환합니다.
+pub fn check_below_lower_band(candle_data: &[CandleData], options: &BandOptions) -> bool {
+    get_percent_b(candle_data, options).is_some_and(|percent_b| percent_b < 0.0)
+}
+
+/// # 밴드 수축 확인
+/// 가장 최근 볼린저 밴드가 켈트너 채널 안에 들어가 있으면 true를 반환합니다.
+pub fn check_squeeze(candle_data: &[CandleData], options: &BandOptions) -> bool {
+    match (get_bollinger_bands(candle_data, options).latest(), get_keltner_channels(candle_data, options).latest()) {
+        (Some(bollinger), Some(keltner)) => bollinger.upper < keltner.upper && bollinger.lower > keltner.lower,
+        _ => false,
+    }
+}
+
+/// # DMI (Directional Movement Index)
+/// 시간 순 Series이며, 기간이 채워지지 않은 앞부분은 null입니다.
+/// - plus_di, minus_di: 상승/하락 방향 지표 (0 ~ 100)
+/// - adx: 추세 강도 (0 ~ 100), 방향과 관계없이 추세가 강할수록 큽니다.
+#[derive(Debug, Clone)]
+pub struct Dmi {
+    pub plus_di: Series,
+    pub minus_di: Series,
+    pub adx: Series,
+}
+
+/// 한 시점의 DMI 값
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct DmiValue {
+    pub plus_di: f64,
+    pub minus_di: f64,
+    pub adx: f64,
+}
+
+impl Dmi {
+    /// 가장 최근 DMI 값, 기간이 채워지지 않았으면 None입니다.
+    pub fn latest(&self) -> Option<DmiValue> {
+        let last = self.adx.len().checked_sub(1)?;
+        Some(DmiValue {
+            plus_di: self.plus_di.f64().unwrap().get(last)?,
+            minus_di: self.minus_di.f64().unwrap().get(last)?,
+            adx: self.adx.f64().unwrap().get(last)?,
+        })
+    }
+}
+
+/// # ADX와 +DI, -DI
+/// 고가 상승폭과 저가 하락폭 중 큰 쪽을 방향 움직임으로 보고, true range와 함께 period 기준 와일더 평활합니다.
+/// 가격이 움직이지 않은 구간처럼 나눗셈이 정의되지 않는 값은 NaN 대신 null입니다.
+pub fn get_dmi(candle_data: &[CandleData], period: usize) -> Dmi {
+    let candle_df = candle_data.as_dataframe();
+    let (highs, lows) = (column_values(&candle_df, "high"), column_values(&candle_df, "low"));
+    let (mut plus_dm, mut minus_dm) = (vec![0.0], vec![0.0]);
+    for i in 1..highs.len() {
+        let up = highs[i] - highs[i-1];
+        let down = lows[i-1] - lows[i];
+        plus_dm.push(if up > down && up > 0.0 { up } else { 0.0 });
+        minus_dm.push(if down > up && down > 0.0 { down } else { 0.0 });
+    }
+
+    let dmi_df = DataFrame::new(vec![
+        Series::from_vec("plus_dm", plus_dm),
+        Series::from_vec("minus_dm", minus_dm),
+        get_true_range_series(candle_data),
+    ]).unwrap()
+        .lazy()
+        .select([
+            col("plus_dm").ewm_mean(wilder_options(period)),
+            col("minus_dm").ewm_mean(wilder_options(period)),
+            col("tr").ewm_mean(wilder_options(period)),
+        ])
+        // 가격이 움직이지 않아 true range가 0이면 방향을 알 수 없으므로 DI를 null로 둡니다.
+        .select([
+            when(col("tr").gt(lit(0.0)))
+                .then(lit(100.0) * col("plus_dm") / col("tr"))
+                .otherwise(lit(NULL))
+                .alias("plus_di"),
+            when(col("tr").gt(lit(0.0)))
+                .then(lit(100.0) * col("minus_dm") / col("tr"))
+                .otherwise(lit(NULL))
+                .alias("minus_di"),
+        ])
+        .collect().unwrap()
+        .lazy()
+        // 가격은 움직였지만 방향 움직임이 없어 +DI, -DI가 모두 0이면 DX는 0입니다.
+        .with_column(
+            when((col("plus_di") + col("minus_di")).gt(lit(0.0)))
+                .then(lit(100.0) * (col("plus_di") - col("minus_di")).abs() / (col("plus_di") + col("minus_di")))
+                .when((col("plus_di") + col("minus_di")).eq(lit(0.0)))
+                .then(lit(0.0))
+                .otherwise(lit(NULL))
+                .ewm_mean(wilder_options(period))
+                .alias("adx"))
+        .collect().unwrap();
+
+    Dmi {
+        plus_di: dmi_df.column("plus_di").unwrap().clone(),
+        minus_di: dmi_df.column("minus_di").unwrap().clone(),
+        adx: dmi_df.column("adx").unwrap().clone(),
+    }
+}
+
+/// # 파라볼릭 SAR 설정
+/// 가속 계수는 step에서 시작해 극점이 갱신될 때마다 step씩 늘어나며, max_step을 넘지 않습니다.
+#[derive(Debug, Clone, Copy)]
+pub struct SarOptions {
+    pub step: f64,
+    pub max_step: f64,
+}
+
+impl Default for SarOptions {
+    fn default() -> Self {
+        SarOptions { step: 0.02, max_step: 0.2 }
+    }
+}
+
+/// # 파라볼릭 SAR
+/// 시간 순 Series이며, uptrend는 각 시점에 상승 추세(SAR이 가격 아래)였는지 여부입니다.
+#[derive(Debug, Clone)]
+pub struct ParabolicSar {
+    pub sar: Series,
+    pub uptrend: Vec<bool>,
+}
+
+impl ParabolicSar {
+    /// 가장 최근 SAR 값과 상승 추세 여부
+    pub fn latest(&self) -> Option<(f64, bool)> {
+        let last = self.sar.len().checked_sub(1)?;
+        Some((self.sar.f64().unwrap().get(last)?, self.uptrend[last]))
+    }
+}
+
+/// # 파라볼릭 SAR 계산
+/// 첫 두 캔들의 종가로 처음 추세를 정하고, 가격이 SAR을 넘어서면 추세를 뒤집고 SAR을 직전 극점으로 옮깁니다.
+pub fn get_parabolic_sar(candle_data: &[CandleData], options: &SarOptions) -> ParabolicSar {
+    let candle_df = candle_data.as_dataframe();
+    let (highs, lows, closes) = (column_values(&candle_df, "high"), column_values(&candle_df, "low"), column_values(&candle_df, "price"));
+    if closes.len() < 2 {
+        return ParabolicSar { sar: Series::new("sar", vec![None::<f64>; closes.len()]), uptrend: vec![true; closes.len()] };
+    }
+
+    let mut uptrend = closes[1] >= closes[0];
+    let mut sar = if uptrend { lows[0] } else { highs[0] };
+    let mut extreme = if uptrend { highs[0] } else { lows[0] };
+    let mut acceleration = options.step;
+    let (mut sars, mut trends) = (vec![None], vec![uptrend]);
+
+    for i in 1..closes.len() {
+        sar += acceleration * (extreme - sar);
+        if uptrend {
+            // 상승 추세의 SAR은 직전 두 캔들의 저가보다 높을 수 없습니다.
+            sar = sar.min(lows[i-1]).min(lows[i.saturating_sub(2)]);
+            if lows[i] < sar {
+                uptrend = false;
+                sar = extreme;
+                extreme = lows[i];
+                acceleration = options.step;
+            } else if highs[i] > extreme {
+                extreme = highs[i];
+                acceleration = (acceleration + options.step).min(options.max_step);
+            }
+        } else {
+            sar = sar.max(highs[i-1]).max(highs[i.saturating_sub(2)]);
+            if highs[i] > sar {
+                uptrend = true;
+                sar = extreme;
+                extreme = highs[i];
+                acceleration = options.step;
+            } else if lows[i] < extreme {
+                extreme = lows[i];
+                acceleration = (acceleration + options.step).min(options.max_step);
+            }
+        }
+        sars.push(Some(sar));
+        trends.push(uptrend);
+    }
+
+    ParabolicSar { sar: Series::new("sar", sars), uptrend: trends }
+}
+
+/// # OBV (On-Balance Volume)
+/// 종가가 오른 캔들의 거래량은 더하고, 내린 캔들의 거래량은 빼서 누적한 시간 순 Series입니다. 첫 캔들은 0입니다.
+pub fn get_obv_series(candle_data: &[CandleData]) -> Series {
+    let candle_df = candle_data.as_dataframe();
+    let (closes, volumes) = (column_values(&candle_df, "price"), column_values(&candle_df, "volume"));
+    let mut obv = 0.0;
+    let obvs = (0..closes.len())
+        .map(|i| {
+            if i > 0 && closes[i] > closes[i-1] {
+                obv += volumes[i];
+            } else if i > 0 && closes[i] < closes[i-1] {
+                obv -= volumes[i];
+            }
+            obv
+        })
+        .collect::<Vec<f64>>();
+    Series::from_vec("obv", obvs)
+}
+
+/// # OBV 상승 확인
+/// OBV가 count개 캔들 전보다 올랐으면 매수세가 들어오고 있다고 보고 true를 반환합니다. 가격은 보지 않습니다.
+pub fn check_obv_rising(candle_data: &[CandleData], count: &usize) -> bool {
+    let obvs = series_values(&get_obv_series(candle_data));
+    obvs.len() > *count && obvs[obvs.len() - 1] > obvs[obvs.len() - 1 - count]
+}
+
+/// # 이동 VWAP
+/// 최근 period개 캔들의 거래대금 합 / 거래량 합인 시간 순 Series이며, 기간이 채워지지 않은 앞부분은 null입니다.
+pub fn get_vwap_series(candle_data: &[CandleData], period: usize) -> Series {
+    let candle_df = candle_data.as_dataframe();
+    let (values, volumes) = (column_values(&candle_df, "value"), column_values(&candle_df, "volume"));
+    let vwaps = (0..values.len())
+        .map(|i| {
+            if i + 1 < period {
+                return None;
+            }
+            let volume = volumes[i + 1 - period..=i].iter().sum::<f64>();
+            (volume > 0.0).then(|| values[i + 1 - period..=i].iter().sum::<f64>() / volume)
+        })
+        .collect::<Vec<Option<f64>>>();
+    Series::new("vwap", vwaps)
+}
+
+/// # 당일 VWAP
+/// 한국 시간 기준 하루가 바뀔 때마다 새로 누적하는 VWAP의 시간 순 Series입니다.
+pub fn get_session_vwap_series(candle_data: &[CandleData]) -> Series {
+    let mut session = "";
+    let (mut value, mut volume) = (0.0, 0.0);
+    let vwaps = candle_data
+        .iter()
+        .rev()
+        .map(|candle| {
+            // candle_date_time_kst는 "2023-07-01T09:00:00" 꼴입니다.
+            let date = candle.candle_date_time_kst.get(..10).unwrap_or(&candle.candle_date_time_kst);
+            if date != session {
+                session = date;
+                value = 0.0;
+                volume = 0.0;
+            }
+            value += candle.candle_acc_trade_price;
+            volume += candle.candle_acc_trade_volume;
+            (volume > 0.0).then(|| value / volume)
+        })
+        .collect::<Vec<Option<f64>>>();
+    Series::new("session_vwap", vwaps)
+}
+
+/// # MFI (Money Flow Index)
+/// 대표 가격((고가 + 저가 + 종가) / 3)이 오른 캔들과 내린 캔들의 자금 흐름을 period개씩 합산하여 0 ~ 100으로 나타낸 시간 순 Series입니다.
+/// 기간이 채워지지 않은 앞부분은 null입니다.
+pub fn get_mfi_series(candle_data: &[CandleData], period: usize) -> Series {
+    let candle_df = candle_data.as_dataframe();
+    let (highs, lows, closes, volumes) = (column_values(&candle_df, "high"), column_values(&candle_df, "low"), column_values(&candle_df, "price"), column_values(&candle_df, "volume"));
+    let typical_prices = (0..closes.len())
+        .map(|i| (highs[i] + lows[i] + closes[i]) / 3.0)
+        .collect::<Vec<f64>>();
+    // 직전보다 대표 가격이 오르면 양수, 내리면 음수인 자금 흐름
+    let flows = (1..typical_prices.len())
+        .map(|i| {
+            let flow = typical_prices[i] * volumes[i];
+            if typical_prices[i] > typical_prices[i-1] { flow } else if typical_prices[i] < typical_prices[i-1] { -flow } else { 0.0 }
+        })
+        .collect::<Vec<f64>>();
+
+    let mfis = (0..closes.len())
+        .map(|i| {
+            if i < period {
+                return None;
+            }
+            let window = &flows[i - period..i];
+            let positive = window.iter().filter(|flow| **flow > 0.0).sum::<f64>();
+            let negative = -window.iter().filter(|flow| **flow < 0.0).sum::<f64>();
+            Some(if negative == 0.0 { 100.0 } else { 100.0 - 100.0 / (1.0 + positive / negative) })
+        })
+        .collect::<Vec<Option<f64>>>();
+    Series::new("mfi", mfis)
+}
+
+/// # 상대 거래량
+/// 각 캔들의 거래량 / 직전 lookback개 캔들의 평균 거래량인 시간 순 Series이며, 직전 캔들이 모자라거나 평균이 0이면 null입니다.
+pub fn get_relative_volume_series(candle_data: &[CandleData], lookback: usize) -> Series {
+    let volumes = column_values(&candle_data.as_dataframe(), "volume");
+    let relative_volumes = (0..volumes.len())
+        .map(|i| {
+            if i < lookback {
+                return None;
+            }
+            let average = volumes[i - lookback..i].iter().sum::<f64>() / lookback as f64;
+            (average > 0.0).then(|| volumes[i] / average)
+        })
+        .collect::<Vec<Option<f64>>>();
+    Series::new("relative_volume", relative_volumes)
+}
+
+/// 가장 최근 캔들의 상대 거래량
+pub fn get_relative_volume(candle_data: &[CandleData], lookback: usize) -> Option<f64> {
+    latest_value(&get_relative_volume_series(candle_data, lookback))
+}
+
+/// # 거래량 급증 확인
+/// 최근 recent_data_bound개 캔들 중 상대 거래량이 multiple 이상인 캔들이 있으면 true를 반환합니다.
+pub fn check_volume_spike(candle_data: &[CandleData], lookback: usize, multiple: f64, recent_data_bound: &usize) -> bool {
+    series_values(&get_relative_volume_series(candle_data, lookback))
+        .into_iter()
+        .rev()
+        .take(*recent_data_bound)
+        .any(|relative_volume| relative_volume.is_some_and(|relative_volume| relative_volume >= multiple))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 시간 순 (한국 시간, 고가, 저가, 종가, 거래량, 거래대금) 목록을 최신 데이터가 앞에 오는 캔들 데이터로 만듭니다.
+    fn candles(rows: &[(&str, f64, f64, f64, f64, f64)]) -> Vec<CandleData> {
+        rows.iter()
+            .rev()
+            .map(|(kst, high, low, close, volume, value)| CandleData {
+                market: "KRW-TEST".to_string(),
+                candle_date_time_utc: kst.to_string(),
+                candle_date_time_kst: kst.to_string(),
+                opening_price: *close,
+                high_price: *high,
+                low_price: *low,
+                trade_price: *close,
+                timestamp: 0,
+                candle_acc_trade_price: *value,
+                candle_acc_trade_volume: *volume,
+                unit: 1,
+            })
+            .collect()
+    }
+
+    // 종가만 다른 캔들, 고가와 저가는 종가 ± 1입니다.
+    fn closes(prices: &[f64]) -> Vec<CandleData> {
+        candles(&prices.iter().map(|price| ("2024-01-01T09:00:00", price + 1.0, price - 1.0, *price, 1.0, *price)).collect::<Vec<_>>())
+    }
+
+    fn assert_series(series: &Series, expected: &[Option<f64>]) {
+        let actual = series_values(series);
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            match (actual, expected) {
+                (Some(actual), Some(expected)) => assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}"),
+                _ => assert_eq!(actual, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn obv_adds_volume_on_up_closes_and_subtracts_on_down_closes() {
+        let data = candles(&[
+            ("2024-01-01T09:00:00", 10.0, 10.0, 10.0, 1.0, 10.0),
+            ("2024-01-01T09:01:00", 11.0, 11.0, 11.0, 2.0, 22.0),
+            ("2024-01-01T09:02:00", 11.0, 11.0, 11.0, 3.0, 33.0),
+            ("2024-01-01T09:03:00", 9.0, 9.0, 9.0, 4.0, 36.0),
+            ("2024-01-01T09:04:00", 12.0, 12.0, 12.0, 5.0, 60.0),
+        ]);
+        assert_series(&get_obv_series(&data), &[Some(0.0), Some(2.0), Some(2.0), Some(-2.0), Some(3.0)]);
+        assert!(check_obv_rising(&data, &1));
+        // 가장 최근 캔들을 빼면 OBV가 2에서 -2로 내려갑니다.
+        assert!(!check_obv_rising(&data[1..], &1));
+    }
+
+    #[test]
+    fn vwap_divides_traded_value_by_volume() {
+        let data = candles(&[
+            ("2024-01-01T23:58:00", 10.0, 10.0, 10.0, 1.0, 10.0),
+            ("2024-01-01T23:59:00", 30.0, 30.0, 30.0, 1.0, 30.0),
+            ("2024-01-02T00:00:00", 20.0, 20.0, 20.0, 2.0, 40.0),
+        ]);
+        // (30 + 40) / (1 + 2)
+        assert_series(&get_vwap_series(&data, 2), &[None, Some(20.0), Some(70.0 / 3.0)]);
+        // 날짜가 바뀌면 새로 누적합니다.
+        assert_series(&get_session_vwap_series(&data), &[Some(10.0), Some(20.0), Some(20.0)]);
+    }
+
+    #[test]
+    fn mfi_compares_positive_and_negative_money_flow() {
+        let data = candles(&[
+            ("2024-01-01T09:00:00", 10.0, 10.0, 10.0, 1.0, 10.0),
+            ("2024-01-01T09:01:00", 11.0, 11.0, 11.0, 1.0, 11.0),
+            ("2024-01-01T09:02:00", 10.0, 10.0, 10.0, 1.0, 10.0),
+            ("2024-01-01T09:03:00", 12.0, 12.0, 12.0, 1.0, 12.0),
+        ]);
+        // 자금 흐름 +11, -10, +12
+        assert_series(&get_mfi_series(&data, 2), &[None, None, Some(100.0 - 100.0 / (1.0 + 11.0 / 10.0)), Some(100.0 - 100.0 / (1.0 + 12.0 / 10.0))]);
+    }
+
+    #[test]
+    fn relative_volume_uses_previous_candles_only() {
+        let data = candles(&[
+            ("2024-01-01T09:00:00", 10.0, 10.0, 10.0, 1.0, 10.0),
+            ("2024-01-01T09:01:00", 10.0, 10.0, 10.0, 3.0, 30.0),
+            ("2024-01-01T09:02:00", 10.0, 10.0, 10.0, 4.0, 40.0),
+        ]);
+        assert_series(&get_relative_volume_series(&data, 2), &[None, None, Some(2.0)]);
+        assert_eq!(get_relative_volume(&data, 2), Some(2.0));
+        assert!(check_volume_spike(&data, 2, 2.0, &1));
+        assert!(!check_volume_spike(&data, 2, 2.5, &1));
+    }
+
+    #[test]
+    fn macd_is_zero_for_constant_prices_and_positive_for_rising_prices() {
+        let flat = get_macd(&closes(&[100.0; 40]), &MacdOptions::default()).latest().unwrap();
+        assert!(flat.line.abs() < 1e-9 && flat.signal.abs() < 1e-9 && flat.histogram.abs() < 1e-9);
+
+        let rising = get_macd(&closes(&(0..40).map(|i| 100.0 + i as f64).collect::<Vec<f64>>()), &MacdOptions::default()).latest().unwrap();
+        assert!(rising.line > 0.0);
+    }
+
+    #[test]
+    fn last_cross_reports_the_most_recent_histogram_sign_change() {
+        let macd = |histogram: Vec<Option<f64>>| Macd {
+            line: Series::new("line", histogram.clone()),
+            signal: Series::new("signal", histogram.clone()),
+            histogram: Series::new("histogram", histogram),
+        };
+        let golden = macd(vec![None, Some(-1.0), Some(0.0), Some(0.5), Some(0.2)]);
+        assert_eq!(golden.last_cross(5), Some(MacdCross::Golden));
+        assert_eq!(golden.last_cross(2), Some(MacdCross::Golden));
+        // 0에서 0.5로 교차한 것은 최근 1개 데이터보다 오래되었습니다.
+        assert_eq!(golden.last_cross(1), None);
+
+        let dead = macd(vec![Some(-1.0), Some(0.5), Some(0.2), Some(-0.3)]);
+        assert_eq!(dead.last_cross(4), Some(MacdCross::Dead));
+        assert_eq!(dead.last_cross(1), Some(MacdCross::Dead));
+    }
+
+    // MACD(1, 3, 3)에서 빠른 EMA는 종가 자체이고, 느린 EMA와 시그널은 alpha 0.5인 보정 없는 EMA입니다.
+    fn short_macd() -> MacdOptions {
+        MacdOptions { fast: 1, slow: 3, signal: 3 }
+    }
+
+    #[test]
+    fn macd_crosses_follow_hand_computed_histogram() {
+        let prices = [10.0, 10.0, 10.0, 10.0, 8.0, 6.0, 7.0, 10.0, 8.0];
+        // 느린 EMA 10, 10, 10, 10, 9, 7.5, 7.25, 8.625, 8.3125
+        // MACD 선 0, 0, -1, -1.5, -0.25, 1.375, -0.3125 (세 번째 캔들부터)
+        // 시그널 0, 0, -0.5, -1, -0.625, 0.375, 0.03125 (세 번째 캔들부터)
+        let macd = get_macd(&closes(&prices), &short_macd());
+        assert_series(&macd.histogram, &[None, None, None, None, Some(-0.5), Some(-0.5), Some(0.375), Some(1.0), Some(-0.34375)]);
+
+        assert_eq!(get_macd(&closes(&prices[..7]), &short_macd()).last_cross(1), Some(MacdCross::Golden));
+        assert_eq!(get_macd(&closes(&prices[..8]), &short_macd()).last_cross(1), None);
+        assert_eq!(get_macd(&closes(&prices), &short_macd()).last_cross(1), Some(MacdCross::Dead));
+    }
+
+    #[test]
+    fn histogram_divergence_compares_the_last_two_extremes() {
+        // 히스토그램 고점은 1 (8번째 캔들, 종가 10)에서 0.65625 (10번째 캔들, 종가 11)로 낮아지고 가격 고점은 높아집니다.
+        let bearish = closes(&[10.0, 10.0, 10.0, 10.0, 8.0, 6.0, 7.0, 10.0, 8.0, 11.0, 11.0]);
+        let divergence = get_macd_divergence(&bearish, &short_macd(), &MacdComponent::Histogram, &RsiDivergenceCheckMode::Peak, &5).unwrap();
+        assert!((divergence.macd_delta - 0.34375).abs() < 1e-9);
+        assert!((divergence.price_delta - 10.0).abs() < 1e-9);
+        // 오른쪽 고점이 최근 1개 데이터보다 오래되었습니다.
+        assert!(!check_macd_divergence(&bearish, &short_macd(), &MacdComponent::Histogram, &RsiDivergenceCheckMode::Peak, &1));
+
+        // 히스토그램 저점은 -0.25 (8번째 캔들, 종가 3)에서 -0.15625 (10번째 캔들, 종가 2)로 높아지고 가격 저점은 낮아집니다.
+        let bullish = closes(&[10.0, 10.0, 10.0, 10.0, 8.0, 6.0, 5.0, 3.0, 4.0, 2.0, 2.0]);
+        let divergence = get_macd_divergence(&bullish, &short_macd(), &MacdComponent::Histogram, &RsiDivergenceCheckMode::Minpoint, &5).unwrap();
+        assert!((divergence.macd_delta - 0.09375).abs() < 1e-9);
+        assert!((divergence.price_delta - 100.0 / 3.0).abs() < 1e-9);
+        assert!(!check_macd_divergence(&bullish, &short_macd(), &MacdComponent::Histogram, &RsiDivergenceCheckMode::Peak, &5));
+    }
+
+    #[test]
+    fn dmi_is_null_for_flat_candles() {
+        let flat = candles(&[("2024-01-01T09:00:00", 5.0, 5.0, 5.0, 1.0, 5.0); 6]);
+        assert!(get_dmi(&flat, 2).latest().is_none());
+
+        // 범위 안에서만 움직여 방향 움직임이 없으면 DI와 ADX는 0입니다.
+        let inside = candles(&[("2024-01-01T09:00:00", 6.0, 4.0, 5.0, 1.0, 5.0); 6]);
+        let DmiValue { plus_di, minus_di, adx } = get_dmi(&inside, 2).latest().unwrap();
+        assert_eq!((plus_di, minus_di, adx), (0.0, 0.0, 0.0));
+
+        let rising = closes(&[10.0, 11.0, 12.0, 13.0, 14.0, 15.0]);
+        let DmiValue { plus_di, minus_di, adx } = get_dmi(&rising, 2).latest().unwrap();
+        assert!(plus_di > 0.0 && minus_di == 0.0 && (adx - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parabolic_sar_flips_when_low_breaks_sar() {
+        let data = closes(&[10.0, 11.0, 12.0, 8.0]);
+        let sar = get_parabolic_sar(&data, &SarOptions::default());
+        // 두 번째, 세 번째 캔들의 SAR은 직전 저가 9로 제한되고, 네 번째 캔들의 저가 7이 SAR 9.24를 깨면서 직전 극점 13으로 뒤집힙니다.
+        assert_series(&sar.sar, &[None, Some(9.0), Some(9.0), Some(13.0)]);
+        assert_eq!(sar.uptrend, vec![true, true, true, false]);
+        assert_eq!(sar.latest(), Some((13.0, false)));
+    }
+
+    #[test]
+    fn bollinger_bands_use_population_std() {
+        let data = closes(&[1.0, 2.0, 3.0, 4.0, 6.0]);
+        let bands = get_bollinger_bands(&data, &BandOptions { period: 3, std_multiple: 2.0, atr_multiple: 1.0 });
+        // 마지막 창 3, 4, 6의 평균은 13 / 3, 모분산은 14 / 9입니다.
+        let (mean, std) = (13.0 / 3.0, (14.0f64 / 9.0).sqrt());
+        assert_series(&bands.middle, &[None, None, Some(2.0), Some(3.0), Some(mean)]);
+        assert_series(&bands.upper, &[None, None, Some(2.0 + 2.0 * (2.0f64 / 3.0).sqrt()), Some(3.0 + 2.0 * (2.0f64 / 3.0).sqrt()), Some(mean + 2.0 * std)]);
+        let latest = bands.latest().unwrap();
+        assert!((latest.lower - (mean - 2.0 * std)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keltner_channels_use_ema_and_atr() {
+        // 종가 10, 고가 11, 저가 9가 이어지면 EMA는 10, true range와 ATR은 2입니다.
+        let flat = closes(&[10.0; 5]);
+        let options = BandOptions { period: 3, std_multiple: 2.0, atr_multiple: 1.5 };
+        let keltner = get_keltner_channels(&flat, &options).latest().unwrap();
+        assert_eq!((keltner.upper, keltner.middle, keltner.lower), (13.0, 10.0, 7.0));
+        // 볼린저 밴드 폭이 0이므로 켈트너 채널 안에 있습니다.
+        assert!(check_squeeze(&flat, &options));
+    }
+
+    #[test]
+    fn squeeze_ends_when_bollinger_bands_leave_keltner_channels() {
+        let options = BandOptions { period: 3, std_multiple: 2.0, atr_multiple: 1.5 };
+        let jump = closes(&[10.0, 10.0, 10.0, 10.0, 16.0]);
+        // EMA는 13, 마지막 true range는 17 - 10 = 7, ATR은 (7 + 2 × (2/3 + 4/9 + 8/27 + 16/81)) / (1 + 2/3 + 4/9 + 8/27 + 16/81) = 827 / 211입니다.
+        let keltner = get_keltner_channels(&jump, &options).latest().unwrap();
+        assert!((keltner.middle - 13.0).abs() < 1e-9);
+        assert!((keltner.lower - (13.0 - 1.5 * 827.0 / 211.0)).abs() < 1e-9);
+        // 볼린저 밴드는 평균 12, 모표준편차 √8이므로 하단 12 - 2√8이 켈트너 하단보다 낮습니다.
+        assert!(!check_squeeze(&jump, &options));
+
+        let root8 = 8.0f64.sqrt();
+        assert!((get_percent_b(&jump, &options).unwrap() - (0.5 + 1.0 / root8)).abs() < 1e-9);
+        assert!((get_bandwidth(&jump, &options).unwrap() - root8 / 3.0).abs() < 1e-9);
+        assert!(!check_below_lower_band(&jump, &options));
+
+        // 평균 8, 모표준편차 √8, 하단 8 - √8 아래의 종가 4
+        let drop = closes(&[10.0, 10.0, 10.0, 10.0, 4.0]);
+        let narrow = BandOptions { std_multiple: 1.0, ..options };
+        assert!((get_percent_b(&drop, &narrow).unwrap() - (0.5 - 2.0 / root8)).abs() < 1e-9);
+        assert!(check_below_lower_band(&drop, &narrow));
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use crate::upbit::journal::{now_millis, OrderSide};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PendingOrder {
+    pub market: String,
+    pub side: OrderSide,
+    pub identifier: String,
+    pub created_at: i64,
+}
+
+/// # 주문 상태 관리자
+/// 종목별로 진행 중인 주문을 추적하여, 한 종목에는 동시에 하나의 주문만 진행되도록 합니다.
+#[derive(Default)]
+pub struct OrderStateManager {
+    pending: Mutex<HashMap<String, PendingOrder>>,
+}
+
+/// 종목 잠금, drop되면 해당 종목의 진행 중 주문이 해제됩니다.
+pub struct MarketOrderGuard {
+    manager: Arc<OrderStateManager>,
+    market: String,
+}
+
+impl Drop for MarketOrderGuard {
+    fn drop(&mut self) {
+        self.manager.pending.lock().unwrap().remove(&self.market);
+    }
+}
+
+impl OrderStateManager {
+    /// 진행 중인 주문이 없을 때만 종목을 잠그고 guard를 반환합니다.
+    pub fn try_lock(self: &Arc<Self>, market: &str, side: OrderSide, identifier: &str) -> Option<MarketOrderGuard> {
+        let mut pending = self.pending.lock().unwrap();
+        if pending.contains_key(market) {
+            return None;
+        }
+
+        pending.insert(market.to_string(), PendingOrder {
+            market: market.to_string(),
+            side,
+            identifier: identifier.to_string(),
+            created_at: now_millis(),
+        });
+        Some(MarketOrderGuard {
+            manager: Arc::clone(self),
+            market: market.to_string(),
+        })
+    }
+
+    pub fn pending(&self) -> Vec<PendingOrder> {
+        self.pending.lock().unwrap().values().cloned().collect()
+    }
+}
+
+/// # 주문 식별자
+/// 같은 캔들에서 나온 같은 방향의 신호는 같은 식별자를 가지므로, 중복 주문이 UPBit에서 거절됩니다.
+pub fn order_identifier(market: &str, side: OrderSide, candle_date_time_utc: &str) -> String {
+    let side = match side {
+        OrderSide::Bid => "bid",
+        OrderSide::Ask => "ask",
+    };
+    format!("yipir-{market}-{side}-{candle_date_time_utc}")
+}
@@ -0,0 +1,188 @@
+use serde::Serialize;
+use crate::upbit::response::Orderbook;
+
+/// # 호가 범위 안의 잔량
+/// 중간가에서 일정 비율 안에 있는 매수/매도 호가의 잔량을 호가 통화 금액으로 나타냅니다.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct Depth {
+    pub bid_value: f64,
+    pub ask_value: f64,
+}
+
+/// # 시장가 체결 예상
+/// 호가를 최우선 호가부터 차례로 소진한다고 보고 계산합니다.
+/// - vwap: 예상 평균 체결가
+/// - slippage: 최우선 호가 대비 평균 체결가가 불리한 정도(%)
+/// - fully_filled: 조회된 호가 안에서 주문을 모두 채울 수 있는지 여부
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct FillEstimate {
+    pub vwap: f64,
+    pub slippage: f64,
+    pub filled_value: f64,
+    pub volume: f64,
+    pub fully_filled: bool,
+}
+
+impl Orderbook {
+    pub fn best_ask(&self) -> Option<f64> {
+        self.orderbook_units.first().map(|unit| unit.ask_price)
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.orderbook_units.first().map(|unit| unit.bid_price)
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.best_ask()? + self.best_bid()?) / 2.0)
+    }
+
+    /// 최우선 매도 호가와 매수 호가의 차이
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+
+    /// 중간가 대비 스프레드(%)
+    pub fn spread_percent(&self) -> Option<f64> {
+        Some(self.spread()? / self.mid_price()? * 100.0)
+    }
+
+    /// (매수 잔량 - 매도 잔량) / 전체 잔량, -1에서 1 사이이며 양수이면 매수 잔량이 더 많습니다.
+    pub fn imbalance(&self) -> f64 {
+        let total = self.total_bid_size + self.total_ask_size;
+        if total > 0.0 { (self.total_bid_size - self.total_ask_size) / total } else { 0.0 }
+    }
+
+    /// 중간가에서 percent(%) 안에 있는 호가 잔량
+    pub fn depth_within(&self, percent: f64) -> Depth {
+        let Some(mid) = self.mid_price() else {
+            return Depth { bid_value: 0.0, ask_value: 0.0 };
+        };
+        let lower = mid * (1.0 - percent / 100.0);
+        let upper = mid * (1.0 + percent / 100.0);
+        Depth {
+            bid_value: self.orderbook_units
+                .iter()
+                .filter(|unit| unit.bid_price >= lower)
+                .map(|unit| unit.bid_price * unit.bid_size)
+                .sum(),
+            ask_value: self.orderbook_units
+                .iter()
+                .filter(|unit| unit.ask_price <= upper)
+                .map(|unit| unit.ask_price * unit.ask_size)
+                .sum(),
+        }
+    }
+
+    /// 호가 통화 amount만큼 시장가 매수할 때의 체결 예상
+    pub fn estimate_buy(&self, amount: f64) -> Option<FillEstimate> {
+        let best = self.best_ask()?;
+        let mut remaining = amount;
+        let mut volume = 0.0;
+        for unit in &self.orderbook_units {
+            if remaining <= 0.0 {
+                break;
+            }
+            let taken = remaining.min(unit.ask_price * unit.ask_size);
+            volume += taken / unit.ask_price;
+            remaining -= taken;
+        }
+        let filled_value = amount - remaining.max(0.0);
+        if volume <= 0.0 {
+            return None;
+        }
+
+        let vwap = filled_value / volume;
+        Some(FillEstimate {
+            vwap,
+            slippage: (vwap / best - 1.0) * 100.0,
+            filled_value,
+            volume,
+            fully_filled: remaining <= 0.0,
+        })
+    }
+
+    /// volume만큼 시장가 매도할 때의 체결 예상
+    pub fn estimate_sell(&self, volume: f64) -> Option<FillEstimate> {
+        let best = self.best_bid()?;
+        let mut remaining = volume;
+        let mut filled_value = 0.0;
+        for unit in &self.orderbook_units {
+            if remaining <= 0.0 {
+                break;
+            }
+            let taken = remaining.min(unit.bid_size);
+            filled_value += taken * unit.bid_price;
+            remaining -= taken;
+        }
+        let filled_volume = volume - remaining.max(0.0);
+        if filled_volume <= 0.0 {
+            return None;
+        }
+
+        let vwap = filled_value / filled_volume;
+        Some(FillEstimate {
+            vwap,
+            slippage: (1.0 - vwap / best) * 100.0,
+            filled_value,
+            volume: filled_volume,
+            fully_filled: remaining <= 0.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::upbit::response::OrderbookUnit;
+    use crate::upbit::risk::{RiskBlock, RiskLimits};
+    use super::*;
+
+    // 매도 호가 100 × 1, 110 × 2, 매수 호가 99 × 1, 90 × 2
+    fn orderbook() -> Orderbook {
+        Orderbook {
+            market: "KRW-TEST".to_string(),
+            timestamp: 0,
+            total_ask_size: 3.0,
+            total_bid_size: 3.0,
+            orderbook_units: vec![
+                OrderbookUnit { ask_price: 100.0, bid_price: 99.0, ask_size: 1.0, bid_size: 1.0 },
+                OrderbookUnit { ask_price: 110.0, bid_price: 90.0, ask_size: 2.0, bid_size: 2.0 },
+            ],
+        }
+    }
+
+    #[test]
+    fn estimate_buy_walks_up_the_asks() {
+        let estimate = orderbook().estimate_buy(210.0).unwrap();
+        // 100에 1개, 110에 1개
+        assert!((estimate.volume - 2.0).abs() < 1e-9);
+        assert!((estimate.vwap - 105.0).abs() < 1e-9);
+        assert!((estimate.slippage - 5.0).abs() < 1e-9);
+        assert!(estimate.fully_filled);
+
+        let estimate = orderbook().estimate_buy(1_000.0).unwrap();
+        assert_eq!(estimate.filled_value, 320.0);
+        assert!(!estimate.fully_filled);
+    }
+
+    #[test]
+    fn estimate_sell_walks_down_the_bids() {
+        let estimate = orderbook().estimate_sell(2.0).unwrap();
+        // 99에 1개, 90에 1개
+        assert_eq!(estimate.filled_value, 189.0);
+        assert!((estimate.vwap - 94.5).abs() < 1e-9);
+        assert!((estimate.slippage - (1.0 - 94.5 / 99.0) * 100.0).abs() < 1e-9);
+        assert!(estimate.fully_filled);
+
+        let estimate = orderbook().estimate_sell(5.0).unwrap();
+        assert_eq!(estimate.volume, 3.0);
+        assert!(!estimate.fully_filled);
+    }
+
+    #[test]
+    fn check_liquidity_rejects_slippage_and_thin_books() {
+        let limits = RiskLimits { max_slippage: Some(1.0), ..RiskLimits::default() };
+        assert_eq!(limits.check_liquidity(&orderbook(), 100.0), Ok(()));
+        assert!(matches!(limits.check_liquidity(&orderbook(), 210.0), Err(RiskBlock::Slippage(_))));
+        assert_eq!(limits.check_liquidity(&orderbook(), 1_000.0), Err(RiskBlock::InsufficientLiquidity(320.0)));
+    }
+}
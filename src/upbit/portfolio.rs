@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::upbit::api::guaranteed_get_tickers;
+use crate::upbit::executor::{OrderExecutor, Position};
+use crate::upbit::journal::{now_millis, JournalEntry, OrderSide};
+use crate::upbit::quote::{fetch_quote_rates, QuoteCurrency, QuoteRates};
+
+/// # 종목별 평가
+/// 미실현 손익은 평균 매수가 대비 현재가로 계산합니다.
+/// 평균 매수가와 현재가는 마켓의 호가 통화 기준이고, 평가액과 손익은 원화로 환산한 값입니다.
+#[derive(Serialize, Debug, Clone)]
+pub struct AssetValuation {
+    pub market: String,
+    pub quote: QuoteCurrency,
+    pub volume: f64,
+    pub avg_buy_price: f64,
+    pub price: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    // 평균 매수가 대비 수익률(%)
+    pub unrealized_pnl_percent: f64,
+}
+
+/// # 포트폴리오 평가
+/// 금액은 모두 원화 기준입니다.
+#[derive(Serialize, Debug, Clone)]
+pub struct PortfolioSnapshot {
+    pub timestamp: i64,
+    pub krw: f64,
+    pub assets: Vec<AssetValuation>,
+    pub holdings_value: f64,
+    pub unrealized_pnl: f64,
+    pub realized_pnl: f64,
+    pub fees: f64,
+    pub total_equity: f64,
+}
+
+/// # 실현 손익
+/// 거래 일지의 체결 내역을 순서대로 이동 평균 단가로 계산하며, 매수 수수료는 단가에, 매도 수수료는 손익에 반영합니다.
+/// 일지에 매수 기록이 없는 보유량을 판 경우에는 원가를 알 수 없으므로 손익에서 제외합니다.
+/// 원화가 아닌 마켓의 손익과 수수료는 rates의 현재 환산 비율로 원화로 바꿉니다.
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct RealizedPnl {
+    pub realized_pnl: f64,
+    pub fees: f64,
+}
+
+pub fn realized_pnl(entries: &[JournalEntry], rates: &QuoteRates) -> RealizedPnl {
+    // 종목별 (추적 중인 보유량, 원가)
+    let mut books: HashMap<&str, (f64, f64)> = HashMap::new();
+    let mut pnl = RealizedPnl::default();
+
+    for entry in entries {
+        let Some(fill) = &entry.fill else {
+            continue;
+        };
+        let quote = QuoteCurrency::of_market(&entry.market);
+        pnl.fees += rates.to_krw(fill.fee, quote);
+        let (volume, cost) = books.entry(entry.market.as_str()).or_insert((0.0, 0.0));
+        match entry.side {
+            OrderSide::Bid => {
+                *volume += fill.volume;
+                *cost += fill.price * fill.volume + fill.fee;
+            }
+            OrderSide::Ask => {
+                let matched = fill.volume.min(*volume);
+                if matched <= 0.0 {
+                    continue;
+                }
+                let average_cost = *cost / *volume;
+                let fee = fill.fee * matched / fill.volume;
+                pnl.realized_pnl += rates.to_krw((fill.price - average_cost) * matched - fee, quote);
+                *cost -= average_cost * matched;
+                *volume -= matched;
+            }
+        }
+    }
+
+    pnl
+}
+
+/// # 포트폴리오 평가
+/// 모든 금액을 원화로 환산하여 평가합니다. prices에 없는 종목은 평균 매수가로 평가합니다.
+pub fn valuate(krw: f64, positions: &[Position], prices: &HashMap<String, f64>, entries: &[JournalEntry], rates: &QuoteRates) -> PortfolioSnapshot {
+    let assets = positions
+        .iter()
+        .map(|position| {
+            let quote = QuoteCurrency::of_market(&position.market);
+            let price = prices.get(&position.market).copied().unwrap_or(position.avg_buy_price);
+            let market_value = rates.to_krw(position.volume * price, quote);
+            let cost = rates.to_krw(position.volume * position.avg_buy_price, quote);
+            AssetValuation {
+                market: position.market.clone(),
+                quote,
+                volume: position.volume,
+                avg_buy_price: position.avg_buy_price,
+                price,
+                market_value,
+                unrealized_pnl: market_value - cost,
+                unrealized_pnl_percent: if cost > 0.0 { (market_value / cost - 1.0) * 100.0 } else { 0.0 },
+            }
+        })
+        .collect::<Vec<AssetValuation>>();
+
+    let holdings_value = assets.iter().map(|asset| asset.market_value).sum::<f64>();
+    let realized = realized_pnl(entries, rates);
+    PortfolioSnapshot {
+        timestamp: now_millis(),
+        krw,
+        unrealized_pnl: assets.iter().map(|asset| asset.unrealized_pnl).sum(),
+        assets,
+        holdings_value,
+        realized_pnl: realized.realized_pnl,
+        fees: realized.fees,
+        total_equity: krw + holdings_value,
+    }
+}
+
+/// # 현재가로 포트폴리오 평가
+/// 보유 종목의 현재가를 한 번에 조회하여 평가합니다. 잔고나 현재가를 조회하지 못하면 에러를 반환합니다.
+pub async fn fetch_portfolio(executor: &OrderExecutor, entries: &[JournalEntry]) -> Result<PortfolioSnapshot, String> {
+    let krw = executor.balance_of("KRW").await?.unwrap_or(0.0);
+    let positions = executor.positions().await?;
+    let markets = positions.iter().map(|position| position.market.clone()).collect::<Vec<String>>();
+    let prices = guaranteed_get_tickers(&markets)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|snapshot| (snapshot.market, snapshot.trade_price))
+        .collect::<HashMap<String, f64>>();
+    let rates = fetch_quote_rates().await.map_err(|e| e.to_string())?;
+    Ok(valuate(krw, &positions, &prices, entries, &rates))
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use clap::ValueEnum;
+use serde::Serialize;
+use crate::upbit::api::{get_tickers, guaranteed_get_tickers, UpbitResponseError};
+use crate::upbit::response::TickerSnapshot;
+
+/// # 호가 통화
+/// 마켓 코드 앞부분의 통화로, KRW-BTC의 KRW입니다.
+#[derive(Serialize, ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum QuoteCurrency {
+    Krw,
+    Btc,
+    Usdt,
+}
+
+impl QuoteCurrency {
+    pub fn code(&self) -> &'static str {
+        match self {
+            QuoteCurrency::Krw => "KRW",
+            QuoteCurrency::Btc => "BTC",
+            QuoteCurrency::Usdt => "USDT",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<QuoteCurrency> {
+        match code {
+            "KRW" => Some(QuoteCurrency::Krw),
+            "BTC" => Some(QuoteCurrency::Btc),
+            "USDT" => Some(QuoteCurrency::Usdt),
+            _ => None,
+        }
+    }
+
+    /// 마켓 코드의 호가 통화, 알 수 없는 마켓은 KRW로 봅니다.
+    pub fn of_market(market: &str) -> QuoteCurrency {
+        market
+            .split_once('-')
+            .and_then(|(quote, _)| QuoteCurrency::from_code(quote))
+            .unwrap_or(QuoteCurrency::Krw)
+    }
+
+    /// 업비트 최소 주문 금액 (호가 통화 기준)
+    pub fn min_order(&self) -> f64 {
+        match self {
+            QuoteCurrency::Krw => 5000.0,
+            QuoteCurrency::Btc => 0.00005,
+            QuoteCurrency::Usdt => 0.5,
+        }
+    }
+
+    /// 업비트 거래 수수료율
+    pub fn fee_rate(&self) -> f64 {
+        match self {
+            QuoteCurrency::Krw => 0.0005,
+            QuoteCurrency::Btc | QuoteCurrency::Usdt => 0.0025,
+        }
+    }
+
+    /// 호가 통화를 원화로 바꿀 때 쓰는 원화 마켓, KRW는 None입니다.
+    pub fn krw_market(&self) -> Option<String> {
+        match self {
+            QuoteCurrency::Krw => None,
+            _ => Some(format!("KRW-{}", self.code())),
+        }
+    }
+
+    /// 이 호가 통화로 base를 거래하는 마켓이 있을 수 있는지 여부
+    /// 호가 통화 자신은 거래 대상이 아니며, 업비트에는 BTC-USDT 마켓이 없습니다.
+    pub fn can_quote(&self, base: &str) -> bool {
+        base != self.code() && base != "KRW" && !(*self == QuoteCurrency::Btc && base == "USDT")
+    }
+}
+
+/// # 원화 환산 비율
+/// 호가 통화 1단위의 원화 가격이며, KRW는 항상 1입니다.
+#[derive(Debug, Clone)]
+pub struct QuoteRates {
+    rates: HashMap<QuoteCurrency, f64>,
+}
+
+impl Default for QuoteRates {
+    fn default() -> Self {
+        QuoteRates { rates: HashMap::from([(QuoteCurrency::Krw, 1.0)]) }
+    }
+}
+
+impl QuoteRates {
+    /// 환산 비율을 구하는 데 필요한 원화 마켓
+    pub fn markets() -> Vec<String> {
+        [QuoteCurrency::Btc, QuoteCurrency::Usdt]
+            .iter()
+            .filter_map(|quote| quote.krw_market())
+            .collect()
+    }
+
+    /// 원화 마켓 가격 목록에서 KRW-BTC, KRW-USDT 가격을 찾아 만듭니다.
+    pub fn from_prices(prices: &HashMap<String, f64>) -> QuoteRates {
+        let mut rates = QuoteRates::default();
+        for quote in [QuoteCurrency::Btc, QuoteCurrency::Usdt] {
+            if let Some(price) = quote.krw_market().and_then(|market| prices.get(&market).copied()) {
+                rates.rates.insert(quote, price);
+            }
+        }
+        rates
+    }
+
+    /// 환산 비율을 모르면 None을 반환합니다.
+    pub fn rate(&self, quote: QuoteCurrency) -> Option<f64> {
+        self.rates.get(&quote).copied()
+    }
+
+    /// 호가 통화 기준 금액을 원화로 환산합니다. 환산 비율을 모르면 0으로 봅니다.
+    pub fn to_krw(&self, amount: f64, quote: QuoteCurrency) -> f64 {
+        amount * self.rate(quote).unwrap_or(0.0)
+    }
+
+    /// from 기준 금액을 to 기준으로 환산합니다. 환산 비율을 모르면 None을 반환합니다.
+    pub fn convert(&self, amount: f64, from: QuoteCurrency, to: QuoteCurrency) -> Option<f64> {
+        Some(amount * self.rate(from)? / self.rate(to)?)
+    }
+}
+
+/// 원화 마켓에서 BTC, USDT의 현재가를 조회하여 환산 비율을 만듭니다.
+pub async fn fetch_quote_rates() -> Result<QuoteRates, UpbitResponseError> {
+    guaranteed_get_tickers(&QuoteRates::markets()).await.map(rates_from_snapshots)
+}
+
+/// 환산 비율을 한 번만 조회합니다. 조회에 실패하면 None을 반환합니다.
+pub async fn try_fetch_quote_rates() -> Option<QuoteRates> {
+    get_tickers(&QuoteRates::markets()).await.ok().map(rates_from_snapshots)
+}
+
+fn rates_from_snapshots(snapshots: Vec<TickerSnapshot>) -> QuoteRates {
+    let prices = snapshots
+        .into_iter()
+        .map(|snapshot| (snapshot.market, snapshot.trade_price))
+        .collect::<HashMap<String, f64>>();
+    QuoteRates::from_prices(&prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_goes_through_krw() {
+        let rates = QuoteRates::from_prices(&HashMap::from([
+            ("KRW-BTC".to_string(), 50_000_000.0),
+            ("KRW-USDT".to_string(), 1_250.0),
+        ]));
+        assert_eq!(rates.convert(0.001, QuoteCurrency::Btc, QuoteCurrency::Krw), Some(50_000.0));
+        assert_eq!(rates.convert(50_000.0, QuoteCurrency::Krw, QuoteCurrency::Usdt), Some(40.0));
+        assert_eq!(rates.convert(0.001, QuoteCurrency::Btc, QuoteCurrency::Usdt), Some(40.0));
+        assert_eq!(rates.to_krw(2.0, QuoteCurrency::Usdt), 2_500.0);
+    }
+
+    #[test]
+    fn convert_without_rate_is_none() {
+        let rates = QuoteRates::default();
+        assert_eq!(rates.convert(1.0, QuoteCurrency::Btc, QuoteCurrency::Krw), None);
+        assert_eq!(rates.to_krw(1.0, QuoteCurrency::Btc), 0.0);
+        assert_eq!(rates.convert(1.0, QuoteCurrency::Krw, QuoteCurrency::Krw), Some(1.0));
+    }
+}
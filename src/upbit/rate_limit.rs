@@ -0,0 +1,54 @@
+use std::sync::OnceLock;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+/// # 요청 빈도 제한기
+/// 요청 사이 간격을 일정하게 유지하여 초당 요청 수를 제한합니다.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn per_second(requests: u32) -> RateLimiter {
+        RateLimiter {
+            interval: Duration::from_secs(1) / requests,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// 다음 요청을 보내도 될 때까지 기다립니다.
+    pub async fn acquire(&self) {
+        let mut next = self.next.lock().await;
+        let now = Instant::now();
+        if *next > now {
+            tokio::time::sleep_until(*next).await;
+        }
+        *next = (*next).max(now) + self.interval;
+    }
+}
+
+/// 업비트 요청 수 제한 그룹
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum RateGroup {
+    // 시세 조회 API, 초당 10회
+    Quotation,
+    // 주문을 제외한 거래소 API, 초당 30회
+    Exchange,
+    // 주문 API, 초당 8회
+    Order,
+}
+
+pub fn limiter_of(group: RateGroup) -> &'static RateLimiter {
+    static QUOTATION: OnceLock<RateLimiter> = OnceLock::new();
+    static EXCHANGE: OnceLock<RateLimiter> = OnceLock::new();
+    static ORDER: OnceLock<RateLimiter> = OnceLock::new();
+
+    match group {
+        RateGroup::Quotation => QUOTATION.get_or_init(|| RateLimiter::per_second(10)),
+        RateGroup::Exchange => EXCHANGE.get_or_init(|| RateLimiter::per_second(30)),
+        RateGroup::Order => ORDER.get_or_init(|| RateLimiter::per_second(8)),
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::fmt;
+use serde::Serialize;
+use crate::upbit::UpbitAccount;
+use crate::upbit::api::{get_order_by_identifier, get_orders, get_orders_page, UpbitResponseError, ORDERS_PAGE_LIMIT};
+use crate::upbit::executor::{fill_of, positions_in_quote, OrderExecutor};
+use crate::upbit::journal::{now_millis, parse_timestamp_millis, JournalEntry, OrderSide};
+use crate::upbit::response::Order;
+use crate::upbit::quote::{fetch_quote_rates, QuoteCurrency};
+use crate::upbit::status::ServiceState;
+
+// 봇이 보낸 주문의 식별자 접두사
+const BOT_IDENTIFIER_PREFIX: &str = "yipir-";
+// 일지에 빠진 주문은 최근 이 기간 안에 만들어진 주문에서 찾습니다.
+const RECOVERY_WINDOW_MILLIS: i64 = 7 * 86_400_000;
+// 상태별로 불러올 최대 페이지 수
+const MAX_ORDER_PAGES: u32 = 50;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OpenOrderSummary {
+    pub market: String,
+    pub side: String,
+    pub uuid: String,
+    pub identifier: Option<String>,
+}
+
+/// # 상태 맞추기 결과
+/// - resumed: 거래 일지에 매수 기록이 있어 이어서 관리하는 종목
+/// - adopted: 거래 일지에 없지만 관리 대상으로 받아들인 종목
+/// - flagged: 거래 일지에 없어 봇이 관리하지 않는 종목
+/// - closed_externally: 거래 일지에는 보유 중이지만 거래소 잔고가 없는 종목
+/// - recovered_orders: 거래소에는 체결되었지만 거래 일지에 빠져 있어 새로 기록한 주문 식별자
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ReconcileReport {
+    pub resumed: Vec<String>,
+    pub adopted: Vec<String>,
+    pub flagged: Vec<String>,
+    pub closed_externally: Vec<String>,
+    pub open_orders: Vec<OpenOrderSummary>,
+    pub recovered_orders: Vec<String>,
+}
+
+impl fmt::Display for ReconcileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "상태 맞추기: 이어서 관리 {}종목, 새로 관리 {}종목, 관리 제외 {}종목, 외부 청산 {}종목, 미체결 주문 {}건, 복구한 주문 {}건",
+                 self.resumed.len(), self.adopted.len(), self.flagged.len(),
+                 self.closed_externally.len(), self.open_orders.len(), self.recovered_orders.len())?;
+        if !self.adopted.is_empty() {
+            writeln!(f, "  거래 일지에 없는 보유 종목을 관리합니다: {}", self.adopted.join(", "))?;
+        }
+        if !self.flagged.is_empty() {
+            writeln!(f, "  거래 일지에 없는 보유 종목은 관리하지 않습니다: {}", self.flagged.join(", "))?;
+        }
+        if !self.closed_externally.is_empty() {
+            writeln!(f, "  봇 밖에서 청산된 종목: {}", self.closed_externally.join(", "))?;
+        }
+        for order in &self.open_orders {
+            writeln!(f, "  미체결 주문: {} {} {} ({})", order.market, order.side, order.uuid, order.identifier.as_deref().unwrap_or("-"))?;
+        }
+        Ok(())
+    }
+}
+
+/// 거래 일지를 순서대로 읽어 마지막 상태가 보유 중인 종목을 반환합니다.
+fn journal_holdings(entries: &[JournalEntry]) -> HashSet<String> {
+    let mut holdings = HashSet::new();
+    for entry in entries.iter().filter(|entry| entry.error.is_none()) {
+        match entry.side {
+            OrderSide::Bid => {
+                holdings.insert(entry.market.clone());
+            }
+            OrderSide::Ask if entry.amount >= 100.0 => {
+                holdings.remove(&entry.market);
+            }
+            OrderSide::Ask => {}
+        }
+    }
+    holdings
+}
+
+/// # 최근 종료된 주문
+/// since 이후에 만들어진 체결 완료(done) 주문과, 일부라도 체결된 뒤 취소(cancel)된 주문을 페이지를 넘겨 가며 불러옵니다.
+/// 시장가 매수는 남은 금액이 취소되어 cancel로 끝나는 경우가 많습니다.
+async fn recent_closed_orders(account: &UpbitAccount, since: i64) -> Result<Vec<Order>, UpbitResponseError> {
+    let mut closed = Vec::new();
+    for state in ["done", "cancel"] {
+        for page in 1..=MAX_ORDER_PAGES {
+            let orders = get_orders_page(account, state, page).await?;
+            let last_page = orders.len() < ORDERS_PAGE_LIMIT;
+            let mut reached_since = false;
+            for order in orders {
+                if parse_timestamp_millis(&order.created_at).is_some_and(|created_at| created_at < since) {
+                    reached_since = true;
+                    continue;
+                }
+                if order.executed_volume > 0.0 {
+                    closed.push(order);
+                }
+            }
+            if last_page || reached_since {
+                break;
+            }
+        }
+    }
+    Ok(closed)
+}
+
+/// # 시작 시 상태 맞추기
+/// 거래소의 잔고, 미체결 주문, 최근 7일 동안 체결된 주문을 거래 일지와 비교합니다.
+/// 일지에 빠진 봇 주문은 주문 시각으로 일지에 복구하고, 일지에 없는 보유 종목은 adopt_unknown에 따라 관리 대상으로 받아들이거나 관리에서 제외합니다.
+/// 관리 대상 종목은 첫 스캔 전에 청산 규칙 추적을 시작합니다. 모의 거래에서는 잔고만 비교합니다.
+/// 보유 종목은 매매하는 호가 통화(quote)의 마켓 기준으로 비교하며, 잔고나 환산 비율을 조회하지 못하면 에러를 반환합니다.
+pub async fn reconcile(executor: &OrderExecutor, state: &ServiceState, quote: QuoteCurrency, adopt_unknown: bool) -> Result<ReconcileReport, String> {
+    let mut report = ReconcileReport::default();
+
+    if let OrderExecutor::Live(account) = executor {
+        match get_orders(account, "wait").await {
+            Ok(orders) => report.open_orders = orders
+                .into_iter()
+                .map(|order| OpenOrderSummary {
+                    market: order.market,
+                    side: order.side,
+                    uuid: order.uuid,
+                    identifier: order.identifier,
+                })
+                .collect(),
+            Err(e) => eprintln!("미체결 주문을 불러올 수 없습니다: {e}"),
+        }
+
+        match recent_closed_orders(account, now_millis() - RECOVERY_WINDOW_MILLIS).await {
+            Ok(orders) => {
+                let known = state.journal.entries()
+                    .into_iter()
+                    .filter_map(|entry| entry.identifier)
+                    .collect::<HashSet<String>>();
+                let missing = orders
+                    .into_iter()
+                    .filter_map(|order| order.identifier)
+                    .filter(|identifier| identifier.starts_with(BOT_IDENTIFIER_PREFIX) && !known.contains(identifier));
+
+                for identifier in missing {
+                    // 체결 내역은 개별 주문 조회에서만 받을 수 있습니다.
+                    let Ok(order) = get_order_by_identifier(account, &identifier).await else {
+                        continue;
+                    };
+                    let fill = fill_of(&order);
+                    let side = if order.side == "bid" { OrderSide::Bid } else { OrderSide::Ask };
+                    let reference_price = fill.as_ref().map_or(0.0, |fill| fill.price);
+                    state.record_trade(JournalEntry {
+                        timestamp: parse_timestamp_millis(&order.created_at).unwrap_or_else(now_millis),
+                        market: order.market,
+                        side,
+                        // 매도 비율은 알 수 없으므로 봇의 매도와 같이 전량 매도로 기록합니다.
+                        amount: match side {
+                            OrderSide::Bid => fill.as_ref().map_or(0.0, |fill| fill.price * fill.volume),
+                            OrderSide::Ask => 100.0,
+                        },
+                        reference_price,
+                        paper: false,
+                        error: None,
+                        reason: Some("reconciled".to_string()),
+                        fill,
+                        identifier: Some(identifier.clone()),
+                    });
+                    report.recovered_orders.push(identifier);
+                }
+            }
+            Err(e) => eprintln!("체결된 주문을 불러올 수 없습니다: {e}"),
+        }
+    }
+
+    let holdings = journal_holdings(&state.journal.entries());
+    let rates = fetch_quote_rates().await.map_err(|e| e.to_string())?;
+    let positions = positions_in_quote(executor.positions().await?, quote, &rates);
+    for position in &positions {
+        if holdings.contains(&position.market) {
+            report.resumed.push(position.market.clone());
+        } else if adopt_unknown {
+            report.adopted.push(position.market.clone());
+        } else {
+            state.set_unmanaged(&position.market);
+            report.flagged.push(position.market.clone());
+        }
+    }
+    report.closed_externally = holdings
+        .into_iter()
+        .filter(|market| positions.iter().all(|position| &position.market != market))
+        .collect();
+
+    let managed = positions
+        .into_iter()
+        .filter(|position| !state.is_unmanaged(&position.market))
+        .collect::<Vec<_>>();
+    state.tracker.sync(&managed, &state.journal);
+
+    Ok(report)
+}
@@ -1,8 +1,10 @@
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use std::str::FromStr;
 use polars::prelude::*;
 use crate::upbit::ops::*;
 
+#[allow(dead_code)]
 #[derive(Deserialize, Debug)]
 pub struct Balance {
     pub currency: String,
@@ -16,6 +18,7 @@ pub struct Balance {
     pub unit_currency: String,
 }
 
+#[allow(dead_code)]
 #[derive(Deserialize, Debug)]
 pub struct CandleData {
     pub market: String,
@@ -36,9 +39,29 @@ pub trait CandleDataOperation {
     fn get_rsi(&self) -> f64;
     fn get_ewm_mean(&self) -> f64;
     fn get_std(&self) -> f64;
+    fn get_atr(&self, period: usize) -> Option<f64>;
     fn get_last_price(&self) -> f64;
     fn check_rsi_divergence(&self, divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> bool;
+    fn get_rsi_divergence(&self, divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> Option<RsiDivergence>;
     fn check_rsi_breaking_peak(&self, count: &usize, rsi_bound: &f64) -> bool;
+    fn get_macd(&self, options: &MacdOptions) -> Macd;
+    fn check_macd_divergence(&self, options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> bool;
+    fn get_macd_divergence(&self, options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> Option<MacdDivergence>;
+    fn get_bollinger_bands(&self, options: &BandOptions) -> Bands;
+    fn get_keltner_channels(&self, options: &BandOptions) -> Bands;
+    fn get_percent_b(&self, options: &BandOptions) -> Option<f64>;
+    fn get_bandwidth(&self, options: &BandOptions) -> Option<f64>;
+    fn check_below_lower_band(&self, options: &BandOptions) -> bool;
+    fn check_squeeze(&self, options: &BandOptions) -> bool;
+    fn get_dmi(&self, period: usize) -> Dmi;
+    fn get_parabolic_sar(&self, options: &SarOptions) -> ParabolicSar;
+    fn get_obv_series(&self) -> Series;
+    fn check_obv_rising(&self, count: &usize) -> bool;
+    fn get_vwap_series(&self, period: usize) -> Series;
+    fn get_session_vwap_series(&self) -> Series;
+    fn get_mfi_series(&self, period: usize) -> Series;
+    fn get_relative_volume(&self, lookback: usize) -> Option<f64>;
+    fn check_volume_spike(&self, lookback: usize, multiple: f64, recent_data_bound: &usize) -> bool;
 }
 
 impl CandleDataOperation for Vec<CandleData> {
@@ -56,34 +79,87 @@ impl CandleDataOperation for Vec<CandleData> {
 
     fn get_std(&self) -> f64 { self.as_slice().get_std() }
 
+    fn get_atr(&self, period: usize) -> Option<f64> { self.as_slice().get_atr(period) }
+
     fn get_last_price(&self) -> f64 {
         self.as_slice().get_last_price()
     }
 
     fn check_rsi_divergence(&self, divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> bool { self.as_slice().check_rsi_divergence(divergence_check_mode, rsi_bound, recent_data_bound) }
 
+    fn get_rsi_divergence(&self, divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> Option<RsiDivergence> { self.as_slice().get_rsi_divergence(divergence_check_mode, rsi_bound, recent_data_bound) }
+
     fn check_rsi_breaking_peak(&self, count: &usize, rsi_bound: &f64) -> bool { self.as_slice().check_rsi_breaking_peak(count, rsi_bound) }
+
+    fn get_macd(&self, options: &MacdOptions) -> Macd { self.as_slice().get_macd(options) }
+
+    fn check_macd_divergence(&self, options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> bool { self.as_slice().check_macd_divergence(options, component, divergence_check_mode, recent_data_bound) }
+
+    fn get_macd_divergence(&self, options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> Option<MacdDivergence> { self.as_slice().get_macd_divergence(options, component, divergence_check_mode, recent_data_bound) }
+
+    fn get_bollinger_bands(&self, options: &BandOptions) -> Bands { self.as_slice().get_bollinger_bands(options) }
+
+    fn get_keltner_channels(&self, options: &BandOptions) -> Bands { self.as_slice().get_keltner_channels(options) }
+
+    fn get_percent_b(&self, options: &BandOptions) -> Option<f64> { self.as_slice().get_percent_b(options) }
+
+    fn get_bandwidth(&self, options: &BandOptions) -> Option<f64> { self.as_slice().get_bandwidth(options) }
+
+    fn check_below_lower_band(&self, options: &BandOptions) -> bool { self.as_slice().check_below_lower_band(options) }
+
+    fn check_squeeze(&self, options: &BandOptions) -> bool { self.as_slice().check_squeeze(options) }
+
+    fn get_dmi(&self, period: usize) -> Dmi { self.as_slice().get_dmi(period) }
+
+    fn get_parabolic_sar(&self, options: &SarOptions) -> ParabolicSar { self.as_slice().get_parabolic_sar(options) }
+
+    fn get_obv_series(&self) -> Series { self.as_slice().get_obv_series() }
+
+    fn check_obv_rising(&self, count: &usize) -> bool { self.as_slice().check_obv_rising(count) }
+
+    fn get_vwap_series(&self, period: usize) -> Series { self.as_slice().get_vwap_series(period) }
+
+    fn get_session_vwap_series(&self) -> Series { self.as_slice().get_session_vwap_series() }
+
+    fn get_mfi_series(&self, period: usize) -> Series { self.as_slice().get_mfi_series(period) }
+
+    fn get_relative_volume(&self, lookback: usize) -> Option<f64> { self.as_slice().get_relative_volume(lookback) }
+
+    fn check_volume_spike(&self, lookback: usize, multiple: f64, recent_data_bound: &usize) -> bool { self.as_slice().check_volume_spike(lookback, multiple, recent_data_bound) }
 }
 
 impl CandleDataOperation for &[CandleData] {
     fn as_dataframe(&self) -> DataFrame {
         let mut timestamps = Vec::new();
+        let mut opens = Vec::new();
+        let mut highs = Vec::new();
+        let mut lows = Vec::new();
         let mut prices = Vec::new();
         let mut volumes = Vec::new();
+        let mut values = Vec::new();
 
         self.iter()
             .rev()
             .for_each(|data| {
                 timestamps.push(data.timestamp);
+                opens.push(data.opening_price);
+                highs.push(data.high_price);
+                lows.push(data.low_price);
                 prices.push(data.trade_price);
                 volumes.push(data.candle_acc_trade_volume);
+                values.push(data.candle_acc_trade_price);
             });
 
+        // price는 종가, value는 거래대금입니다.
         let timestamp_series = Series::from_vec("timestamp", timestamps);
+        let open_series = Series::from_vec("open", opens);
+        let high_series = Series::from_vec("high", highs);
+        let low_series = Series::from_vec("low", lows);
         let price_series = Series::from_vec("price", prices);
         let volume_series = Series::from_vec("volume", volumes);
+        let value_series = Series::from_vec("value", values);
 
-        DataFrame::new(vec![timestamp_series, price_series, volume_series]).unwrap()
+        DataFrame::new(vec![timestamp_series, open_series, high_series, low_series, price_series, volume_series, value_series]).unwrap()
     }
 
     fn get_rsi(&self) -> f64 {
@@ -96,6 +172,8 @@ impl CandleDataOperation for &[CandleData] {
 
     fn get_std(&self) -> f64 { get_std(self) }
 
+    fn get_atr(&self, period: usize) -> Option<f64> { get_atr(self, period) }
+
     fn get_last_price(&self) -> f64 {
         self
             .last().unwrap()
@@ -106,20 +184,401 @@ impl CandleDataOperation for &[CandleData] {
         check_rsi_divergence(self, divergence_check_mode, rsi_bound, recent_data_bound)
     }
 
+    fn get_rsi_divergence(&self, divergence_check_mode: &RsiDivergenceCheckMode, rsi_bound: &f64, recent_data_bound: &usize) -> Option<RsiDivergence> {
+        get_rsi_divergence(self, divergence_check_mode, rsi_bound, recent_data_bound)
+    }
+
     fn check_rsi_breaking_peak(&self, count: &usize, rsi_bound: &f64) -> bool {
-        check_rsi_breaking_peak(&self, count, rsi_bound)
+        check_rsi_breaking_peak(self, count, rsi_bound)
+    }
+
+    fn get_macd(&self, options: &MacdOptions) -> Macd {
+        get_macd(self, options)
+    }
+
+    fn check_macd_divergence(&self, options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> bool {
+        check_macd_divergence(self, options, component, divergence_check_mode, recent_data_bound)
+    }
+
+    fn get_macd_divergence(&self, options: &MacdOptions, component: &MacdComponent, divergence_check_mode: &RsiDivergenceCheckMode, recent_data_bound: &usize) -> Option<MacdDivergence> {
+        get_macd_divergence(self, options, component, divergence_check_mode, recent_data_bound)
+    }
+
+    fn get_bollinger_bands(&self, options: &BandOptions) -> Bands {
+        get_bollinger_bands(self, options)
+    }
+
+    fn get_keltner_channels(&self, options: &BandOptions) -> Bands {
+        get_keltner_channels(self, options)
+    }
+
+    fn get_percent_b(&self, options: &BandOptions) -> Option<f64> {
+        get_percent_b(self, options)
+    }
+
+    fn get_bandwidth(&self, options: &BandOptions) -> Option<f64> {
+        get_bandwidth(self, options)
+    }
+
+    fn check_below_lower_band(&self, options: &BandOptions) -> bool {
+        check_below_lower_band(self, options)
+    }
+
+    fn check_squeeze(&self, options: &BandOptions) -> bool {
+        check_squeeze(self, options)
+    }
+
+    fn get_dmi(&self, period: usize) -> Dmi {
+        get_dmi(self, period)
+    }
+
+    fn get_parabolic_sar(&self, options: &SarOptions) -> ParabolicSar {
+        get_parabolic_sar(self, options)
+    }
+
+    fn get_obv_series(&self) -> Series {
+        get_obv_series(self)
+    }
+
+    fn check_obv_rising(&self, count: &usize) -> bool {
+        check_obv_rising(self, count)
+    }
+
+    fn get_vwap_series(&self, period: usize) -> Series {
+        get_vwap_series(self, period)
+    }
+
+    fn get_session_vwap_series(&self) -> Series {
+        get_session_vwap_series(self)
+    }
+
+    fn get_mfi_series(&self, period: usize) -> Series {
+        get_mfi_series(self, period)
+    }
+
+    fn get_relative_volume(&self, lookback: usize) -> Option<f64> {
+        get_relative_volume(self, lookback)
+    }
+
+    fn check_volume_spike(&self, lookback: usize, multiple: f64, recent_data_bound: &usize) -> bool {
+        check_volume_spike(self, lookback, multiple, recent_data_bound)
     }
 }
 
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct Order {
+    pub uuid: String,
+    pub side: String,
+    pub ord_type: String,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub price: Option<f64>,
+    pub state: String,
+    pub market: String,
+    pub created_at: String,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub volume: Option<f64>,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub remaining_volume: Option<f64>,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub paid_fee: f64,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub executed_volume: f64,
+    pub trades_count: i64,
+    #[serde(default)]
+    pub identifier: Option<String>,
+    // 개별 주문 조회에서만 채워집니다.
+    #[serde(default)]
+    pub trades: Vec<OrderTrade>,
+}
+
+#[allow(dead_code)]
 #[derive(Deserialize, Debug)]
+pub struct OrderTrade {
+    pub market: String,
+    pub uuid: String,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub price: f64,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub volume: f64,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub funds: f64,
+    pub side: String,
+}
+
+/// # 마켓 정보
+/// market_warning과 market_event는 isDetails=true로 조회했을 때만 채워집니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Ticker {
     pub market: String,
     pub korean_name: String,
     pub english_name: String,
+    #[serde(default)]
+    pub market_warning: MarketWarning,
+    #[serde(default)]
+    pub market_event: MarketEvent,
+}
+
+/// 투자 유의 종목 지정 여부
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum MarketWarning {
+    #[default]
+    None,
+    Caution,
+}
+
+/// # 마켓 경보
+/// - warning: 유의 종목 지정 여부
+/// - caution: 주의 종목 지정 사유별 여부 (가격 급등락, 거래량 급등, 입금량 급등, 글로벌 가격 차이, 소수 계정 거래 집중)
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct MarketEvent {
+    #[serde(default)]
+    pub warning: bool,
+    #[serde(default)]
+    pub caution: HashMap<String, bool>,
+}
+
+impl Ticker {
+    /// 호가 통화, KRW-BTC의 KRW
+    pub fn quote(&self) -> &str {
+        self.market.split_once('-').map_or(self.market.as_str(), |(quote, _)| quote)
+    }
+
+    /// 거래 대상 통화, KRW-BTC의 BTC
+    pub fn base(&self) -> &str {
+        self.market.split_once('-').map_or(self.market.as_str(), |(_, base)| base)
+    }
+
+    /// 투자 유의 또는 유의 종목으로 지정되었는지 여부
+    pub fn is_warned(&self) -> bool {
+        self.market_warning == MarketWarning::Caution || self.market_event.warning
+    }
+
+    /// 주의 종목 사유가 하나라도 있는지 여부
+    pub fn has_caution(&self) -> bool {
+        self.market_event.caution.values().any(|flagged| *flagged)
+    }
+
+    /// 주의 종목 사유 목록
+    pub fn caution_reasons(&self) -> Vec<&str> {
+        let mut reasons = self.market_event.caution
+            .iter()
+            .filter(|(_, flagged)| **flagged)
+            .map(|(reason, _)| reason.as_str())
+            .collect::<Vec<&str>>();
+        reasons.sort();
+        reasons
+    }
+}
+
+/// 전일 종가 대비 가격 변화
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PriceChange {
+    Rise,
+    Even,
+    Fall,
+}
+
+/// # 현재가 스냅샷
+/// 변화량과 변화율은 전일 종가 대비이며, 24시간 누적값은 최근 24시간 기준입니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TickerSnapshot {
+    pub market: String,
+    pub opening_price: f64,
+    pub high_price: f64,
+    pub low_price: f64,
+    pub trade_price: f64,
+    pub prev_closing_price: f64,
+    pub change: PriceChange,
+    pub change_price: f64,
+    pub change_rate: f64,
+    pub signed_change_price: f64,
+    pub signed_change_rate: f64,
+    pub trade_volume: f64,
+    pub acc_trade_price_24h: f64,
+    pub acc_trade_volume_24h: f64,
+    pub highest_52_week_price: f64,
+    pub highest_52_week_date: String,
+    pub lowest_52_week_price: f64,
+    pub lowest_52_week_date: String,
+    pub timestamp: i64,
+}
+
+/// 체결을 일으킨 쪽, Bid는 매수자가 매도 호가를 가져간 체결입니다.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TradeSide {
+    Ask,
+    Bid,
+}
+
+/// # 체결 내역
+/// sequential_id는 체결마다 고유하며, 다음 페이지를 조회할 때 cursor로 사용합니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, Clone)]
+pub struct TradeTick {
+    pub market: String,
+    pub trade_date_utc: String,
+    pub trade_time_utc: String,
+    pub timestamp: i64,
+    pub trade_price: f64,
+    pub trade_volume: f64,
+    pub prev_closing_price: f64,
+    pub change_price: f64,
+    pub ask_bid: TradeSide,
+    pub sequential_id: i64,
+}
+
+/// # 호가
+/// orderbook_units는 최우선 호가부터 순서대로 옵니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Orderbook {
+    pub market: String,
+    pub timestamp: i64,
+    pub total_ask_size: f64,
+    pub total_bid_size: f64,
+    pub orderbook_units: Vec<OrderbookUnit>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct OrderbookUnit {
+    pub ask_price: f64,
+    pub bid_price: f64,
+    pub ask_size: f64,
+    pub bid_size: f64,
+}
+
+/// # 입출금 내역
+/// 입금 조회와 출금 조회, 출금 요청의 응답이 같은 형식입니다.
+/// state는 입금이면 PROCESSING, ACCEPTED, CANCELLED 등, 출금이면 WAITING, PROCESSING, DONE, FAILED 등입니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Transfer {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub uuid: String,
+    pub currency: String,
+    #[serde(default)]
+    pub net_type: Option<String>,
+    #[serde(default)]
+    pub txid: Option<String>,
+    pub state: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub done_at: Option<String>,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub amount: f64,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub fee: f64,
+    pub transaction_type: String,
+}
+
+/// # 출금 가능 정보
+/// 출금 수수료, 지갑 상태, 1회/1일 출금 한도를 담습니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct WithdrawChance {
+    pub currency: WithdrawCurrency,
+    pub account: Balance,
+    pub withdraw_limit: WithdrawLimit,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct WithdrawCurrency {
+    pub code: String,
+    #[serde(deserialize_with = "f64_from_str")]
+    pub withdraw_fee: f64,
+    pub is_coin: bool,
+    pub wallet_state: WalletState,
+    pub wallet_support: Vec<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+pub struct WithdrawLimit {
+    pub currency: String,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub minimum: Option<f64>,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub onetime: Option<f64>,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub daily: Option<f64>,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub remaining_daily: Option<f64>,
+    #[serde(default, deserialize_with = "f64_option_from_str")]
+    pub remaining_daily_krw: Option<f64>,
+    pub fixed: i32,
+    pub can_withdraw: bool,
+}
+
+/// 입출금 지갑 상태, 새로 생긴 상태는 Unknown으로 받습니다.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletState {
+    Working,
+    WithdrawOnly,
+    DepositOnly,
+    Paused,
+    Unsupported,
+    #[serde(other)]
+    Unknown,
+}
+
+/// # 입출금 현황
+/// block_state는 블록 동기화 상태로 normal, delayed, inactive 중 하나이며, 블록체인이 없는 통화는 비어 있습니다.
+#[allow(dead_code)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WalletStatus {
+    pub currency: String,
+    pub wallet_state: WalletState,
+    #[serde(default)]
+    pub block_state: Option<String>,
+    #[serde(default)]
+    pub block_height: Option<i64>,
+    #[serde(default)]
+    pub block_updated_at: Option<String>,
+    #[serde(default)]
+    pub block_elapsed_minutes: Option<i64>,
+    pub net_type: String,
+    pub network_name: String,
+}
+
+/// # API 키
+/// expire_at은 "2021-03-22T18:01:42+09:00" 꼴입니다.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub access_key: String,
+    pub expire_at: String,
+}
+
+// UPBit이 요청을 거절했을 때의 응답 형식
+#[derive(Deserialize, Debug)]
+pub struct UpbitErrorBody {
+    pub error: UpbitErrorDetail,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpbitErrorDetail {
+    pub name: String,
+    pub message: String,
 }
 
 fn f64_from_str<'de, D>(deserializer: D) -> Result<f64, D::Error>
     where D: Deserializer<'de> {
     let s = String::deserialize(deserializer).unwrap();
     f64::from_str(&s).map_err(de::Error::custom)
-}
\ No newline at end of file
+}
+
+fn f64_option_from_str<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where D: Deserializer<'de> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(s) => f64::from_str(&s).map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,563 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use serde::Serialize;
+use crate::upbit::executor::Position;
+use crate::upbit::journal::{now_millis, OrderSide, TradeJournal};
+use crate::upbit::ops::SarOptions;
+use crate::upbit::response::{CandleData, CandleDataOperation, Orderbook};
+
+/// # 추적 손절 방식
+/// Percent는 보유 중 최고가 대비 지정한 퍼센트(%)만큼, Atr은 최고가에서 ATR의 multiplier배만큼 하락하면 청산합니다.
+/// ParabolicSar는 진입 후 상승 추세였던 파라볼릭 SAR이 하락 추세로 뒤집히면 청산합니다.
+#[derive(Debug, Clone, Copy)]
+pub enum TrailingStop {
+    Percent(f64),
+    Atr { multiplier: f64, period: usize },
+    ParabolicSar(SarOptions),
+}
+
+/// # 청산 규칙
+/// 전략의 매도 신호와 별개로, 매 스캔마다 보유 종목에 적용되는 청산 규칙입니다.
+/// 비율은 모두 평균 매수가 대비 퍼센트(%)이며, 지정하지 않은 규칙은 적용되지 않습니다.
+#[derive(Debug, Clone, Default)]
+pub struct ExitRules {
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+    pub trailing_stop: Option<TrailingStop>,
+    pub max_holding: Option<Duration>,
+}
+
+/// 추적 손절에 필요한 지표 값, Sar의 uptrend는 가장 최근 SAR이 상승 추세인지 여부입니다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailingIndicator {
+    Atr(f64),
+    Sar { sar: f64, uptrend: bool },
+}
+
+impl ExitRules {
+    /// 추적 손절에 필요한 지표 값을 구합니다. Atr 방식은 ATR, ParabolicSar 방식은 가장 최근 SAR과 추세입니다.
+    /// 캔들이 모자라 지표를 구할 수 없으면 None이며, 이때는 추적 손절을 건너뜁니다.
+    pub fn trailing_indicator(&self, candle_data: &[CandleData]) -> Option<TrailingIndicator> {
+        match &self.trailing_stop {
+            Some(TrailingStop::Atr { period, .. }) => candle_data.get_atr(*period).map(TrailingIndicator::Atr),
+            Some(TrailingStop::ParabolicSar(options)) => candle_data
+                .get_parabolic_sar(options)
+                .latest()
+                .map(|(sar, uptrend)| TrailingIndicator::Sar { sar, uptrend }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitReason {
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+    MaxHoldingTime,
+    // 최근 체결에서 매도 체결이 우세함
+    SellingPressure,
+}
+
+impl ExitReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::StopLoss => "stop-loss",
+            ExitReason::TakeProfit => "take-profit",
+            ExitReason::TrailingStop => "trailing-stop",
+            ExitReason::MaxHoldingTime => "max-holding-time",
+            ExitReason::SellingPressure => "selling-pressure",
+        }
+    }
+}
+
+struct TrackedPosition {
+    entry_price: f64,
+    highest_price: f64,
+    opened_at: i64,
+    // 진입 후 파라볼릭 SAR이 상승 추세인 것을 본 적이 있는지 여부
+    sar_uptrend_seen: bool,
+}
+
+/// # 보유 종목 추적기
+/// 보유 종목별 진입가, 보유 중 최고가, 진입 시각을 기억하여 청산 규칙을 판단합니다.
+#[derive(Default)]
+pub struct PositionTracker {
+    positions: Mutex<HashMap<String, TrackedPosition>>,
+}
+
+impl PositionTracker {
+    /// 현재 보유 종목에 맞춰 추적 목록을 갱신합니다.
+    /// 새로 발견한 종목의 진입 시각은 거래 일지의 마지막 매수 기록을, 없으면 현재 시각을 사용합니다.
+    pub fn sync(&self, positions: &[Position], journal: &TradeJournal) {
+        let mut tracked = self.positions.lock().unwrap();
+        tracked.retain(|market, _| positions.iter().any(|position| &position.market == market));
+
+        for position in positions {
+            match tracked.get_mut(&position.market) {
+                Some(existing) => existing.entry_price = position.avg_buy_price,
+                None => {
+                    let opened_at = journal.entries()
+                        .iter()
+                        .rev()
+                        .find(|entry| entry.market == position.market && entry.side == OrderSide::Bid && entry.error.is_none())
+                        .map_or_else(now_millis, |entry| entry.timestamp);
+                    tracked.insert(position.market.clone(), TrackedPosition {
+                        entry_price: position.avg_buy_price,
+                        highest_price: position.avg_buy_price,
+                        opened_at,
+                        sar_uptrend_seen: false,
+                    });
+                }
+            }
+        }
+    }
+
+    /// # 청산 판단
+    /// price로 최고가를 갱신한 뒤 청산해야 하면 그 이유를 반환합니다.
+    /// ATR, 파라볼릭 SAR 방식의 추적 손절은 ExitRules::trailing_indicator로 구한 indicator가 주어진 경우에만 판단합니다.
+    /// 파라볼릭 SAR은 진입 후 상승 추세를 한 번이라도 본 뒤 하락 추세가 되었을 때만 청산하므로, 진입할 때 이미 하락 추세였다면 상승 추세로 돌아설 때까지 기다립니다.
+    pub fn evaluate(&self, rules: &ExitRules, market: &str, price: f64, indicator: Option<TrailingIndicator>) -> Option<ExitReason> {
+        let mut tracked = self.positions.lock().unwrap();
+        let position = tracked.get_mut(market)?;
+        position.highest_price = position.highest_price.max(price);
+        if position.entry_price <= 0.0 {
+            return None;
+        }
+
+        let profit = (price / position.entry_price - 1.0) * 100.0;
+        if rules.stop_loss.is_some_and(|stop_loss| profit <= -stop_loss) {
+            return Some(ExitReason::StopLoss);
+        }
+
+        let trailing_hit = match &rules.trailing_stop {
+            Some(TrailingStop::Percent(percent)) => price <= position.highest_price * (1.0 - percent / 100.0),
+            Some(TrailingStop::Atr { multiplier, .. }) => match indicator {
+                Some(TrailingIndicator::Atr(atr)) => price <= position.highest_price - multiplier * atr,
+                _ => false,
+            },
+            Some(TrailingStop::ParabolicSar(_)) => match indicator {
+                Some(TrailingIndicator::Sar { uptrend: true, .. }) => {
+                    position.sar_uptrend_seen = true;
+                    false
+                }
+                Some(TrailingIndicator::Sar { uptrend: false, .. }) => position.sar_uptrend_seen,
+                _ => false,
+            },
+            None => false,
+        };
+        if trailing_hit {
+            return Some(ExitReason::TrailingStop);
+        }
+
+        if rules.take_profit.is_some_and(|take_profit| profit >= take_profit) {
+            return Some(ExitReason::TakeProfit);
+        }
+
+        if rules.max_holding.is_some_and(|max_holding| now_millis() - position.opened_at >= max_holding.as_millis() as i64) {
+            return Some(ExitReason::MaxHoldingTime);
+        }
+
+        None
+    }
+}
+
+/// # 포트폴리오 위험 한도
+/// 노출 한도는 매매하는 마켓의 호가 통화 기준이고, 손실 한도는 원화 기준입니다. 지정하지 않은 한도는 적용되지 않습니다.
+/// 일일 손익은 그날(UTC) 첫 평가액 대비 현재 평가액으로, 실현 손익과 미실현 손익을 모두 포함합니다.
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    pub max_open_positions: Option<usize>,
+    pub max_market_exposure: Option<f64>,
+    pub max_total_exposure: Option<f64>,
+    // 일일 손실이 이 금액 이상이면 새 매수를 멈춥니다.
+    pub daily_loss_limit: Option<f64>,
+    // 일일 손실이 이 금액 이상이면 킬 스위치를 작동시킵니다.
+    pub kill_switch_loss: Option<f64>,
+    pub max_orders_per_minute: Option<usize>,
+    // 호가로 추정한 시장가 매수의 슬리피지(%)가 이보다 크면 매수하지 않습니다.
+    pub max_slippage: Option<f64>,
+}
+
+impl RiskLimits {
+    /// # 유동성 확인
+    /// budget만큼 시장가 매수했을 때 호가 잔량이 충분하고 예상 슬리피지가 한도 안에 있는지 확인합니다.
+    pub fn check_liquidity(&self, orderbook: &Orderbook, budget: f64) -> Result<(), RiskBlock> {
+        let Some(max_slippage) = self.max_slippage else {
+            return Ok(());
+        };
+        let Some(estimate) = orderbook.estimate_buy(budget) else {
+            return Err(RiskBlock::InsufficientLiquidity(0.0));
+        };
+        if !estimate.fully_filled {
+            return Err(RiskBlock::InsufficientLiquidity(estimate.filled_value));
+        }
+        if estimate.slippage > max_slippage {
+            return Err(RiskBlock::Slippage(estimate.slippage));
+        }
+        Ok(())
+    }
+}
+
+/// 위험 한도에 걸려 주문하지 않은 이유
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskBlock {
+    KillSwitch,
+    DailyLossLimit(f64),
+    MaxOpenPositions(usize),
+    MarketExposure(f64),
+    TotalExposure(f64),
+    OrderRate(usize),
+    // 시세 조회가 실패하고 있어 시장 데이터가 오래되었을 수 있음
+    StaleMarketData,
+    // 예상 슬리피지(%)가 한도를 넘음
+    Slippage(f64),
+    // 조회된 호가로 채울 수 있는 금액이 주문 금액보다 적음
+    InsufficientLiquidity(f64),
+}
+
+impl fmt::Display for RiskBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskBlock::KillSwitch => write!(f, "킬 스위치가 작동 중입니다."),
+            RiskBlock::DailyLossLimit(loss) => write!(f, "일일 손실 {loss:.0} KRW로 손실 한도에 도달했습니다."),
+            RiskBlock::MaxOpenPositions(count) => write!(f, "보유 종목 수 {count}개로 최대 보유 종목 수에 도달했습니다."),
+            RiskBlock::MarketExposure(exposure) => write!(f, "주문 후 종목 노출 {exposure}이(가) 종목당 최대 노출을 넘습니다."),
+            RiskBlock::TotalExposure(exposure) => write!(f, "주문 후 전체 노출 {exposure}이(가) 최대 노출을 넘습니다."),
+            RiskBlock::OrderRate(count) => write!(f, "최근 1분 동안 {count}회 주문하여 분당 최대 주문 수에 도달했습니다."),
+            RiskBlock::StaleMarketData => write!(f, "시세 조회가 원활하지 않아 새로 매수하지 않습니다."),
+            RiskBlock::Slippage(slippage) => write!(f, "예상 슬리피지 {slippage:.2}%가 최대 슬리피지를 넘습니다."),
+            RiskBlock::InsufficientLiquidity(available) => write!(f, "호가 잔량으로 {available}까지만 체결할 수 있습니다."),
+        }
+    }
+}
+
+/// 한 스캔 시점의 보유 현황, 종목별 평가액(호가 통화 기준)입니다.
+#[derive(Debug, Default)]
+pub struct Exposure {
+    markets: HashMap<String, f64>,
+}
+
+impl Exposure {
+    /// 현재가를 모르는 종목은 평균 매수가로 평가합니다.
+    pub fn new(positions: &[Position], prices: &HashMap<String, f64>) -> Exposure {
+        Exposure {
+            markets: positions
+                .iter()
+                .map(|position| {
+                    let price = prices.get(&position.market).copied().unwrap_or(position.avg_buy_price);
+                    (position.market.clone(), position.volume * price)
+                })
+                .collect(),
+        }
+    }
+
+    /// 매수가 승인된 금액을 더합니다. 같은 스캔의 다음 매수 판단에 반영됩니다.
+    pub fn add(&mut self, market: &str, amount: f64) {
+        *self.markets.entry(market.to_string()).or_insert(0.0) += amount;
+    }
+
+    pub fn contains(&self, market: &str) -> bool {
+        self.markets.contains_key(market)
+    }
+
+    fn total(&self) -> f64 {
+        self.markets.values().sum()
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RiskSnapshot {
+    pub killed: bool,
+    pub daily_pnl: f64,
+    pub orders_last_minute: usize,
+}
+
+// 청산에 실패한 종목은 이 시간부터 실패할 때마다 두 배씩, 최대 LIQUIDATION_RETRY_MAX_MILLIS까지 기다렸다 다시 시도합니다.
+const LIQUIDATION_RETRY_BASE_MILLIS: i64 = 5_000;
+const LIQUIDATION_RETRY_MAX_MILLIS: i64 = 600_000;
+
+/// # 포트폴리오 위험 관리자
+/// 킬 스위치, 일일 손익, 최근 주문 시각을 관리하며 새 주문이 위험 한도 안에 있는지 판단합니다.
+/// 킬 스위치가 작동한 동안에는 종목별 청산 실패를 기록하여 재시도 간격을 늘립니다.
+#[derive(Default)]
+pub struct RiskGuard {
+    killed: AtomicBool,
+    // 킬 스위치가 작동한 시각, 청산 주문 식별자에 사용합니다.
+    killed_at: Mutex<Option<i64>>,
+    // 종목별 (연속 청산 실패 횟수, 다음 시도 시각)
+    liquidation_failures: Mutex<HashMap<String, (u32, i64)>>,
+    // 이번 킬 스위치 작동에서 종목별로 낸 청산 주문 수, 주문 식별자를 시도마다 다르게 만듭니다.
+    liquidation_attempts: Mutex<HashMap<String, u32>>,
+    // (UTC 기준 날짜, 그날 첫 평가액, 현재 평가액)
+    daily_equity: Mutex<Option<(i64, f64, f64)>>,
+    order_times: Mutex<VecDeque<i64>>,
+}
+
+impl RiskGuard {
+    pub fn is_killed(&self) -> bool {
+        self.killed.load(Ordering::SeqCst)
+    }
+
+    /// 꺼져 있던 킬 스위치를 켜거나 끄면 청산 실패 기록을 지우고 새 작동으로 봅니다.
+    pub fn set_killed(&self, killed: bool) {
+        if self.killed.swap(killed, Ordering::SeqCst) != killed {
+            *self.killed_at.lock().unwrap() = killed.then(now_millis);
+            self.liquidation_failures.lock().unwrap().clear();
+            self.liquidation_attempts.lock().unwrap().clear();
+        }
+    }
+
+    /// 이번 킬 스위치 작동에서 market을 청산하는 다음 주문의 식별자
+    /// 업비트는 이미 사용한 식별자를 거절하므로, 일부만 체결된 뒤 남은 수량을 다시 청산할 수 있도록 시도마다 번호를 붙입니다.
+    pub fn next_liquidation_identifier(&self, market: &str) -> String {
+        let mut attempts = self.liquidation_attempts.lock().unwrap();
+        let attempt = attempts.entry(market.to_string()).or_insert(0);
+        *attempt += 1;
+        format!("yipir-{market}-kill-{}-{attempt}", self.killed_at.lock().unwrap().unwrap_or(0))
+    }
+
+    /// 청산에 실패한 적이 없거나 재시도 대기 시간이 지났으면 true를 반환합니다.
+    pub fn liquidation_ready(&self, market: &str) -> bool {
+        self.liquidation_failures.lock().unwrap().get(market).is_none_or(|(_, retry_at)| now_millis() >= *retry_at)
+    }
+
+    /// 청산 실패를 기록하고 연속 실패 횟수를 반환합니다.
+    pub fn record_liquidation_failure(&self, market: &str) -> u32 {
+        let mut failures = self.liquidation_failures.lock().unwrap();
+        let (count, retry_at) = failures.entry(market.to_string()).or_insert((0, 0));
+        *count += 1;
+        let delay = LIQUIDATION_RETRY_BASE_MILLIS.saturating_mul(1 << (*count - 1).min(16)).min(LIQUIDATION_RETRY_MAX_MILLIS);
+        *retry_at = now_millis() + delay;
+        *count
+    }
+
+    pub fn record_liquidation_success(&self, market: &str) {
+        self.liquidation_failures.lock().unwrap().remove(market);
+    }
+
+    /// 평가액을 갱신하고 일일 손익을 반환합니다. 날짜가 바뀌면 기준 평가액을 새로 잡습니다.
+    pub fn update_equity(&self, equity: f64) -> f64 {
+        let today = now_millis() / 86_400_000;
+        let mut daily_equity = self.daily_equity.lock().unwrap();
+        let start = match *daily_equity {
+            Some((day, start, _)) if day == today => start,
+            _ => equity,
+        };
+        *daily_equity = Some((today, start, equity));
+        equity - start
+    }
+
+    pub fn daily_pnl(&self) -> f64 {
+        self.daily_equity.lock().unwrap().map_or(0.0, |(_, start, equity)| equity - start)
+    }
+
+    /// 최근 1분 동안의 주문 수가 한도 안이면 주문 시각을 기록하고 통과시킵니다.
+    /// 새 매수에만 적용하며, 매도(전략 매도, 청산 규칙, 킬 스위치)는 한도에 막히지 않습니다.
+    pub fn try_record_order(&self, limits: &RiskLimits) -> Result<(), RiskBlock> {
+        let now = now_millis();
+        let mut order_times = self.order_times.lock().unwrap();
+        while order_times.front().is_some_and(|time| now - time >= 60_000) {
+            order_times.pop_front();
+        }
+        if limits.max_orders_per_minute.is_some_and(|max| order_times.len() >= max) {
+            return Err(RiskBlock::OrderRate(order_times.len()));
+        }
+        order_times.push_back(now);
+        Ok(())
+    }
+
+    /// # 매수 판단
+    /// market을 budget만큼 새로 매수해도 위험 한도 안에 있는지 확인합니다.
+    /// 주문 빈도 한도는 여기서 판단하지 않으므로 try_record_order를 따로 호출해야 합니다.
+    pub fn check_entry(&self, limits: &RiskLimits, exposure: &Exposure, market: &str, budget: f64) -> Result<(), RiskBlock> {
+        if self.is_killed() {
+            return Err(RiskBlock::KillSwitch);
+        }
+
+        let daily_pnl = self.daily_pnl();
+        if limits.daily_loss_limit.is_some_and(|limit| -daily_pnl >= limit) {
+            return Err(RiskBlock::DailyLossLimit(-daily_pnl));
+        }
+
+        let held = exposure.markets.get(market).copied();
+        if held.is_none() && limits.max_open_positions.is_some_and(|max| exposure.markets.len() >= max) {
+            return Err(RiskBlock::MaxOpenPositions(exposure.markets.len()));
+        }
+
+        let market_exposure = held.unwrap_or(0.0) + budget;
+        if limits.max_market_exposure.is_some_and(|max| market_exposure > max) {
+            return Err(RiskBlock::MarketExposure(market_exposure));
+        }
+
+        let total_exposure = exposure.total() + budget;
+        if limits.max_total_exposure.is_some_and(|max| total_exposure > max) {
+            return Err(RiskBlock::TotalExposure(total_exposure));
+        }
+
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> RiskSnapshot {
+        let now = now_millis();
+        RiskSnapshot {
+            killed: self.is_killed(),
+            daily_pnl: self.daily_pnl(),
+            orders_last_minute: self.order_times.lock().unwrap().iter().filter(|time| now - **time < 60_000).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::upbit::executor::PaperAccount;
+    use super::*;
+
+    fn position(market: &str, volume: f64, avg_buy_price: f64) -> Position {
+        Position { market: market.to_string(), volume, locked: 0.0, avg_buy_price }
+    }
+
+    // 평균 매수가 100으로 KRW-TEST를 보유한 추적기
+    fn held_tracker(name: &str) -> PositionTracker {
+        let path = std::env::temp_dir().join(format!("yipir-risk-{}-{name}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = TradeJournal::open(&path).unwrap();
+        let tracker = PositionTracker::default();
+        tracker.sync(&[position("KRW-TEST", 1.0, 100.0)], &journal);
+        let _ = std::fs::remove_file(&path);
+        tracker
+    }
+
+    #[test]
+    fn stop_loss_and_take_profit_use_entry_price() {
+        let rules = ExitRules { stop_loss: Some(5.0), take_profit: Some(10.0), ..ExitRules::default() };
+        let tracker = held_tracker("stop-loss");
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 96.0, None), None);
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 95.0, None), Some(ExitReason::StopLoss));
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 110.0, None), Some(ExitReason::TakeProfit));
+        assert_eq!(tracker.evaluate(&rules, "KRW-OTHER", 50.0, None), None);
+    }
+
+    #[test]
+    fn trailing_stops_follow_highest_price() {
+        let rules = ExitRules { trailing_stop: Some(TrailingStop::Percent(10.0)), ..ExitRules::default() };
+        let tracker = held_tracker("trailing-percent");
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 120.0, None), None);
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 109.0, None), None);
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 108.0, None), Some(ExitReason::TrailingStop));
+
+        let rules = ExitRules { trailing_stop: Some(TrailingStop::Atr { multiplier: 2.0, period: 14 }), ..ExitRules::default() };
+        let tracker = held_tracker("trailing-atr");
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 110.0, Some(TrailingIndicator::Atr(3.0))), None);
+        // ATR을 구할 수 없으면 판단하지 않습니다.
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 90.0, None), None);
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 104.0, Some(TrailingIndicator::Atr(3.0))), Some(ExitReason::TrailingStop));
+    }
+
+    #[test]
+    fn parabolic_sar_exits_only_after_flipping_from_uptrend() {
+        let rules = ExitRules { trailing_stop: Some(TrailingStop::ParabolicSar(SarOptions::default())), ..ExitRules::default() };
+        let tracker = held_tracker("trailing-sar");
+        let down = Some(TrailingIndicator::Sar { sar: 105.0, uptrend: false });
+        let up = Some(TrailingIndicator::Sar { sar: 95.0, uptrend: true });
+        // 진입할 때 이미 하락 추세였던 SAR은 무시합니다.
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 100.0, down), None);
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 101.0, up), None);
+        assert_eq!(tracker.evaluate(&rules, "KRW-TEST", 100.0, down), Some(ExitReason::TrailingStop));
+    }
+
+    #[test]
+    fn max_holding_time_exits_old_positions() {
+        let rules = ExitRules { max_holding: Some(Duration::ZERO), ..ExitRules::default() };
+        assert_eq!(held_tracker("max-holding").evaluate(&rules, "KRW-TEST", 100.0, None), Some(ExitReason::MaxHoldingTime));
+    }
+
+    #[test]
+    fn check_entry_applies_exposure_limits() {
+        let guard = RiskGuard::default();
+        let exposure = Exposure::new(&[position("KRW-A", 2.0, 100.0)], &HashMap::from([("KRW-A".to_string(), 150.0)]));
+        let limits = RiskLimits {
+            max_open_positions: Some(1),
+            max_market_exposure: Some(400.0),
+            max_total_exposure: Some(500.0),
+            ..RiskLimits::default()
+        };
+
+        assert_eq!(guard.check_entry(&limits, &exposure, "KRW-B", 10.0), Err(RiskBlock::MaxOpenPositions(1)));
+        // KRW-A의 노출은 2 × 150 = 300입니다.
+        assert_eq!(guard.check_entry(&limits, &exposure, "KRW-A", 100.0), Ok(()));
+        assert_eq!(guard.check_entry(&limits, &exposure, "KRW-A", 150.0), Err(RiskBlock::MarketExposure(450.0)));
+
+        let limits = RiskLimits { max_total_exposure: Some(350.0), ..RiskLimits::default() };
+        assert_eq!(guard.check_entry(&limits, &exposure, "KRW-B", 100.0), Err(RiskBlock::TotalExposure(400.0)));
+
+        guard.set_killed(true);
+        assert_eq!(guard.check_entry(&RiskLimits::default(), &exposure, "KRW-B", 1.0), Err(RiskBlock::KillSwitch));
+    }
+
+    #[test]
+    fn daily_loss_is_measured_from_first_equity_of_the_day() {
+        let guard = RiskGuard::default();
+        let limits = RiskLimits { daily_loss_limit: Some(100.0), ..RiskLimits::default() };
+        assert_eq!(guard.update_equity(1_000.0), 0.0);
+        assert_eq!(guard.update_equity(950.0), -50.0);
+        assert_eq!(guard.check_entry(&limits, &Exposure::default(), "KRW-A", 1.0), Ok(()));
+        assert_eq!(guard.update_equity(900.0), -100.0);
+        assert_eq!(guard.check_entry(&limits, &Exposure::default(), "KRW-A", 1.0), Err(RiskBlock::DailyLossLimit(100.0)));
+    }
+
+    #[test]
+    fn order_rate_limit_counts_recorded_orders() {
+        let guard = RiskGuard::default();
+        let limits = RiskLimits { max_orders_per_minute: Some(2), ..RiskLimits::default() };
+        assert_eq!(guard.try_record_order(&limits), Ok(()));
+        assert_eq!(guard.try_record_order(&limits), Ok(()));
+        assert_eq!(guard.try_record_order(&limits), Err(RiskBlock::OrderRate(2)));
+        assert_eq!(guard.snapshot().orders_last_minute, 2);
+    }
+
+    #[test]
+    fn liquidation_failures_back_off_per_market() {
+        let guard = RiskGuard::default();
+        guard.set_killed(true);
+        assert_eq!(guard.record_liquidation_failure("KRW-A"), 1);
+        assert_eq!(guard.record_liquidation_failure("KRW-A"), 2);
+        assert!(!guard.liquidation_ready("KRW-A"));
+        assert!(guard.liquidation_ready("KRW-B"));
+
+        guard.record_liquidation_success("KRW-A");
+        assert!(guard.liquidation_ready("KRW-A"));
+
+        // 킬 스위치를 다시 켜면 실패 기록을 지웁니다.
+        guard.record_liquidation_failure("KRW-A");
+        guard.set_killed(false);
+        guard.set_killed(true);
+        assert!(guard.liquidation_ready("KRW-A"));
+    }
+
+    #[test]
+    fn partially_filled_liquidation_is_retried_with_a_new_identifier() {
+        let guard = RiskGuard::default();
+        guard.set_killed(true);
+        let mut paper = PaperAccount::new(1_000_000.0);
+        paper.fill_buy("KRW-A", 10_000.0, 100.0).unwrap();
+
+        // 첫 청산 주문은 절반만 체결됩니다.
+        let first = guard.next_liquidation_identifier("KRW-A");
+        paper.register_identifier(&first).unwrap();
+        paper.fill_sell("KRW-A", 50.0, 100.0).unwrap();
+        guard.record_liquidation_success("KRW-A");
+        assert!(paper.balance_of("KRW-A").is_some());
+
+        // 같은 식별자는 거절되므로 남은 수량은 새 식별자로 청산합니다.
+        let retry = guard.next_liquidation_identifier("KRW-A");
+        assert_ne!(retry, first);
+        assert!(paper.register_identifier(&first).is_err());
+        paper.register_identifier(&retry).unwrap();
+        paper.fill_sell("KRW-A", 100.0, 100.0).unwrap();
+        assert!(paper.balance_of("KRW-A").is_none());
+    }
+}
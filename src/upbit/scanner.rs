@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::{self, Duration, Instant};
+use crate::upbit::api::{CandleUnit, get_candle_data};
+use crate::upbit::response::CandleData;
+
+/// # 스캔 설정
+/// concurrency만큼의 종목을 동시에 요청하며, 실제 요청 속도는 시세 조회 API 빈도 제한을 따릅니다.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub concurrency: usize,
+    pub market_timeout: Duration,
+    pub scan_deadline: Duration,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            concurrency: 8,
+            market_timeout: Duration::from_secs(5),
+            scan_deadline: Duration::from_secs(50),
+        }
+    }
+}
+
+/// # 스캔 결과
+/// 기한 내에 받아온 캔들 데이터만 담으며, 받지 못한 종목은 failed에 남깁니다.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub candle_datas: Vec<Vec<CandleData>>,
+    pub failed: Vec<String>,
+    pub elapsed: Duration,
+}
+
+/// # 캔들 데이터 동시 스캔
+/// 각 종목은 market_timeout까지 재시도하며, scan_deadline이 지나면 그때까지 받은 결과만 반환합니다.
+pub async fn scan_candles(tickers: Vec<String>, unit: CandleUnit, count: u8, options: &ScanOptions) -> ScanReport {
+    let started = Instant::now();
+    let deadline = started + options.scan_deadline;
+    let semaphore = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for ticker in tickers.iter().cloned() {
+        let semaphore = Arc::clone(&semaphore);
+        let market_timeout = options.market_timeout;
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.unwrap();
+            let fetched = time::timeout(market_timeout, async {
+                loop {
+                    match get_candle_data(&ticker, &unit, count).await {
+                        Ok(candle_data) => return candle_data,
+                        Err(_) => time::sleep(Duration::from_millis(100)).await,
+                    }
+                }
+            }).await;
+            (ticker, fetched.ok())
+        });
+    }
+
+    let mut report = ScanReport::default();
+    let mut finished = Vec::new();
+    loop {
+        match time::timeout_at(deadline, tasks.join_next()).await {
+            Ok(Some(Ok((ticker, Some(candle_data))))) if !candle_data.is_empty() => {
+                finished.push(ticker);
+                report.candle_datas.push(candle_data);
+            }
+            Ok(Some(Ok((ticker, _)))) => {
+                finished.push(ticker.clone());
+                report.failed.push(ticker);
+            }
+            // 요청 중 패닉이 난 작업은 어느 종목인지 알 수 없으므로 마지막에 한꺼번에 실패 처리합니다.
+            Ok(Some(Err(_))) => continue,
+            Ok(None) => break,
+            Err(_) => {
+                tasks.abort_all();
+                break;
+            }
+        }
+    }
+
+    report.failed.extend(
+        tickers
+            .into_iter()
+            .filter(|ticker| !finished.contains(ticker)));
+    report.elapsed = started.elapsed();
+    report
+}
@@ -0,0 +1,115 @@
+use serde::Serialize;
+use crate::upbit::ops::RsiDivergenceCheckMode;
+use crate::upbit::response::{CandleData, CandleDataOperation};
+use crate::upbit::strategy::is_buy_signal;
+
+// 매수 조건의 RSI 저점 기준
+const RSI_BOUND: f64 = 30.0;
+// 각 항목이 최고점(1.0)이 되는 기준값
+const FULL_DIVERGENCE_RSI_DELTA: f64 = 10.0;
+const FULL_DIVERGENCE_PRICE_DELTA_PERCENT: f64 = 1.0;
+const FULL_EWM_DISTANCE_PERCENT: f64 = 2.0;
+const FULL_VOLUME_SURGE_RATIO: f64 = 3.0;
+// 거래량 급증 판단에 사용할 직전 캔들 개수
+const VOLUME_LOOKBACK: usize = 20;
+
+/// # 매수 점수 가중치
+/// 각 항목은 0.0에서 1.0 사이로 정규화한 뒤 가중치를 곱해 더합니다.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreWeights {
+    pub divergence: f64,
+    pub rsi: f64,
+    pub ewm: f64,
+    pub volume: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights {
+            divergence: 1.0,
+            rsi: 1.0,
+            ewm: 1.0,
+            volume: 1.0,
+        }
+    }
+}
+
+/// # 매수 후보 선정 설정
+/// 한 번의 스캔에서 점수가 높은 순서로 최대 top_n 종목까지만 매수합니다.
+#[derive(Debug, Clone)]
+pub struct ScoringConfig {
+    pub weights: ScoreWeights,
+    pub top_n: usize,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig {
+            weights: ScoreWeights::default(),
+            top_n: 3,
+        }
+    }
+}
+
+/// # 매수 점수
+/// 항목별 점수는 가중치를 곱하기 전의 정규화된 값입니다.
+#[derive(Serialize, Debug, Clone)]
+pub struct SignalScore {
+    pub market: String,
+    pub total: f64,
+    // RSI 다이버전스를 이루는 두 저점의 RSI 상승폭과 가격 하락폭
+    pub divergence: f64,
+    // 현재 RSI가 저점 기준(30)보다 얼마나 낮은지
+    pub rsi: f64,
+    // 현재 가격이 EWM보다 얼마나 낮은지
+    pub ewm: f64,
+    // 직전 캔들 평균 대비 현재 거래량
+    pub volume: f64,
+}
+
+/// # 매수 점수 계산
+/// 매수 조건을 만족하지 않는 종목은 None을 반환합니다.
+pub fn score_buy_candidate(candle_data: &[CandleData], weights: &ScoreWeights) -> Option<SignalScore> {
+    if !is_buy_signal(candle_data) {
+        return None;
+    }
+
+    let divergence = candle_data
+        .get_rsi_divergence(&RsiDivergenceCheckMode::Minpoint, &RSI_BOUND, &5)
+        .map_or(0.0, |divergence| {
+            let rsi_delta = (divergence.rsi_delta / FULL_DIVERGENCE_RSI_DELTA).clamp(0.0, 1.0);
+            let price_delta = (divergence.price_delta / FULL_DIVERGENCE_PRICE_DELTA_PERCENT).clamp(0.0, 1.0);
+            (rsi_delta + price_delta) / 2.0
+        });
+    // RSI가 10 미만이면 get_rsi가 -1을 반환하므로 최고점으로 처리됩니다.
+    let rsi = ((RSI_BOUND - candle_data.get_rsi()) / RSI_BOUND).clamp(0.0, 1.0);
+
+    let price = candle_data[0].trade_price;
+    let ewm_mean = candle_data.get_ewm_mean();
+    let ewm = ((ewm_mean - price) / ewm_mean * 100.0 / FULL_EWM_DISTANCE_PERCENT).clamp(0.0, 1.0);
+
+    let volume = candle_data
+        .get_relative_volume(VOLUME_LOOKBACK)
+        .map_or(0.0, |relative_volume| ((relative_volume - 1.0) / (FULL_VOLUME_SURGE_RATIO - 1.0)).clamp(0.0, 1.0));
+
+    Some(SignalScore {
+        market: candle_data[0].market.clone(),
+        total: weights.divergence * divergence + weights.rsi * rsi + weights.ewm * ewm + weights.volume * volume,
+        divergence,
+        rsi,
+        ewm,
+        volume,
+    })
+}
+
+/// # 매수 후보 순위
+/// 매수 조건을 만족하는 종목을 점수가 높은 순서로 정렬하여 캔들 데이터와 함께 반환합니다.
+pub fn rank_buy_candidates<'a>(candle_datas: &'a [Vec<CandleData>], weights: &ScoreWeights) -> Vec<(&'a [CandleData], SignalScore)> {
+    let mut candidates = candle_datas
+        .iter()
+        .filter(|candle_data| !candle_data.is_empty())
+        .filter_map(|candle_data| score_buy_candidate(candle_data, weights).map(|score| (candle_data.as_slice(), score)))
+        .collect::<Vec<(&[CandleData], SignalScore)>>();
+    candidates.sort_by(|(_, left), (_, right)| right.total.total_cmp(&left.total));
+    candidates
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+use crate::upbit::UpbitAccount;
+
+const KEYSTORE_PBKDF2_ROUNDS: u32 = 200_000;
+
+/// # 비밀 문자열
+/// drop 시점에 메모리를 0으로 덮어쓰는 문자열 래퍼입니다.
+/// `Debug`로 출력해도 내용이 드러나지 않으며, 실제 값은 `expose()`로만 꺼낼 수 있습니다.
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    pub fn new(value: String) -> SecretString {
+        SecretString(Zeroizing::new(value))
+    }
+
+    pub fn expose(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(**redacted**)")
+    }
+}
+
+// 키스토어 파일 관련 에러
+#[derive(Debug)]
+pub enum KeystoreError {
+    Io(std::io::Error),
+    MalformedFile,
+    WrongPassphrase,
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeystoreError::Io(e) => write!(f, "키스토어 파일을 읽거나 쓸 수 없습니다: {e}"),
+            KeystoreError::MalformedFile => f.write_str("키스토어 파일 형식이 잘못되었습니다."),
+            KeystoreError::WrongPassphrase => f.write_str("키스토어 암호가 일치하지 않습니다."),
+        }
+    }
+}
+
+// 디스크에 저장되는 키스토어 형식, 각 필드는 16진수 문자열입니다.
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// 암호화되기 전의 키 쌍, 복호화한 키도 drop 시점에 0으로 덮어씁니다.
+#[derive(Serialize, Deserialize)]
+struct KeystorePlain {
+    access_key: Zeroizing<String>,
+    secret_key: Zeroizing<String>,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, KEYSTORE_PBKDF2_ROUNDS, key.as_mut());
+    key
+}
+
+/// # 키스토어 불러오기
+/// passphrase로 암호화된 키스토어 파일을 복호화하여 `UpbitAccount`를 만듭니다.
+/// 키는 PBKDF2-HMAC-SHA256으로 유도하고 AES-256-GCM으로 복호화합니다.
+pub fn load_keystore(path: &Path, passphrase: &str) -> Result<UpbitAccount, KeystoreError> {
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+    use aes_gcm::aead::Aead;
+
+    let raw = std::fs::read_to_string(path).map_err(KeystoreError::Io)?;
+    let file: KeystoreFile = serde_json::from_str(&raw).map_err(|_| KeystoreError::MalformedFile)?;
+    let salt = base16ct::mixed::decode_vec(&file.salt).map_err(|_| KeystoreError::MalformedFile)?;
+    let nonce = base16ct::mixed::decode_vec(&file.nonce).map_err(|_| KeystoreError::MalformedFile)?;
+    let ciphertext = base16ct::mixed::decode_vec(&file.ciphertext).map_err(|_| KeystoreError::MalformedFile)?;
+    if nonce.len() != 12 {
+        return Err(KeystoreError::MalformedFile);
+    }
+
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| KeystoreError::MalformedFile)?;
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| KeystoreError::WrongPassphrase)?
+    );
+
+    let mut plain: KeystorePlain = serde_json::from_slice(&plaintext).map_err(|_| KeystoreError::MalformedFile)?;
+    // 문자열 버퍼를 복사하지 않고 옮기며, 비워진 원래 값은 그대로 drop됩니다.
+    Ok(UpbitAccount::new(std::mem::take(&mut *plain.access_key), std::mem::take(&mut *plain.secret_key)))
+}
+
+/// # 키스토어 저장
+/// 주어진 키 쌍을 passphrase로 암호화하여 path에 저장합니다.
+pub fn save_keystore(path: &Path, passphrase: &str, access_key: &str, secret_key: &str) -> Result<(), KeystoreError> {
+    use aes_gcm::{Aes256Gcm, KeyInit, AeadCore};
+    use aes_gcm::aead::{Aead, OsRng, rand_core::RngCore};
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let plain = Zeroizing::new(serde_json::to_vec(&KeystorePlain {
+        access_key: Zeroizing::new(access_key.to_string()),
+        secret_key: Zeroizing::new(secret_key.to_string()),
+    }).map_err(|_| KeystoreError::MalformedFile)?);
+
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new_from_slice(key.as_ref()).map_err(|_| KeystoreError::MalformedFile)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plain.as_slice())
+        .map_err(|_| KeystoreError::MalformedFile)?;
+
+    let file = KeystoreFile {
+        salt: base16ct::lower::encode_string(&salt),
+        nonce: base16ct::lower::encode_string(&nonce),
+        ciphertext: base16ct::lower::encode_string(&ciphertext),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&file).unwrap()).map_err(KeystoreError::Io)
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use tokio::task::JoinSet;
+use tokio::time::{Duration, Instant};
+use crate::upbit::UpbitAccount;
+use crate::upbit::api::{cancel_order, get_orders};
+
+/// # 종료 요약
+/// 서비스가 종료되면서 처리한 작업을 정리합니다.
+#[derive(Debug, Default)]
+pub struct ShutdownSummary {
+    pub scans: usize,
+    pub orders_recorded: usize,
+    pub drained_tasks: usize,
+    pub abandoned_tasks: usize,
+    pub cancelled_orders: usize,
+}
+
+impl fmt::Display for ShutdownSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "스캔 {}회, 기록된 주문 {}건, 완료를 기다린 주문 작업 {}건, 중단된 주문 작업 {}건, 취소한 미체결 주문 {}건",
+               self.scans, self.orders_recorded, self.drained_tasks, self.abandoned_tasks, self.cancelled_orders)
+    }
+}
+
+/// SIGINT(Ctrl+C) 또는 SIGTERM을 받을 때까지 기다립니다.
+pub async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate()).expect("SIGTERM 핸들러를 등록할 수 없습니다.");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// # 주문 작업 정리
+/// 진행 중인 주문 작업이 끝나기를 timeout까지 기다리고, 그때까지 끝나지 않은 작업은 중단합니다.
+/// (완료된 작업 수, 중단된 작업 수)를 반환합니다.
+pub async fn drain_order_tasks(tasks: &mut JoinSet<()>, timeout: Duration) -> (usize, usize) {
+    let deadline = Instant::now() + timeout;
+    let mut drained = 0;
+
+    while !tasks.is_empty() {
+        match tokio::time::timeout_at(deadline, tasks.join_next()).await {
+            Ok(Some(_)) => drained += 1,
+            Ok(None) => break,
+            Err(_) => {
+                let abandoned = tasks.len();
+                tasks.abort_all();
+                return (drained, abandoned);
+            }
+        }
+    }
+
+    (drained, 0)
+}
+
+/// # 미체결 지정가 주문 취소
+/// 대기 중인 지정가 주문을 모두 취소하고, 취소에 성공한 주문 수를 반환합니다.
+pub async fn cancel_open_limit_orders(account: &UpbitAccount) -> usize {
+    let open_orders = match get_orders(account, "wait").await {
+        Ok(orders) => orders,
+        Err(e) => {
+            eprintln!("미체결 주문을 불러올 수 없습니다: {e:?}");
+            return 0;
+        }
+    };
+
+    let mut cancelled = 0;
+    for order in open_orders.iter().filter(|order| order.ord_type == "limit") {
+        match cancel_order(account, &order.uuid).await {
+            Ok(_) => cancelled += 1,
+            Err(e) => eprintln!("{} 주문({})을 취소할 수 없습니다: {e:?}", order.market, order.uuid),
+        }
+    }
+    cancelled
+}
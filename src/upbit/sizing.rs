@@ -0,0 +1,192 @@
+use crate::upbit::backtest::BacktestReport;
+use crate::upbit::response::{CandleData, CandleDataOperation};
+
+/// 변동성 측정 방식
+#[derive(Debug, Clone, Copy)]
+pub enum VolatilityMeasure {
+    // 종가 표준편차
+    Std,
+    // 주어진 기간의 ATR
+    Atr(usize),
+}
+
+impl VolatilityMeasure {
+    /// 현재가 대비 변동성(%)을 반환합니다. 캔들이 모자라 ATR을 구할 수 없으면 None입니다.
+    fn percent_of(&self, candle_data: &[CandleData]) -> Option<f64> {
+        let volatility = match self {
+            VolatilityMeasure::Std => candle_data.get_std(),
+            VolatilityMeasure::Atr(period) => candle_data.get_atr(*period)?,
+        };
+        Some(volatility / candle_data[0].trade_price * 100.0)
+    }
+}
+
+/// 고정 위험 방식에서 사용할 손절 폭
+#[derive(Debug, Clone, Copy)]
+pub enum StopDistance {
+    // 진입가 대비 퍼센트(%)
+    Percent(f64),
+    // 진입가에서 ATR의 multiplier배
+    Atr { multiplier: f64, period: usize },
+}
+
+impl StopDistance {
+    /// 현재가 대비 손절 폭(%)을 반환합니다. 캔들이 모자라 ATR을 구할 수 없으면 None입니다.
+    fn percent_of(&self, candle_data: &[CandleData]) -> Option<f64> {
+        match self {
+            StopDistance::Percent(percent) => Some(*percent),
+            StopDistance::Atr { multiplier, period } => Some(multiplier * candle_data.get_atr(*period)? / candle_data[0].trade_price * 100.0),
+        }
+    }
+}
+
+/// # 켈리 기준 통계
+/// 백테스트의 매수-매도 한 쌍 수익률로 구한 승률과 평균 손익비입니다.
+#[derive(Debug, Clone, Copy)]
+pub struct KellyStats {
+    pub win_rate: f64,
+    pub payoff_ratio: f64,
+}
+
+impl KellyStats {
+    /// 이긴 거래와 진 거래가 모두 있어야 손익비를 구할 수 있으므로, 하나라도 없으면 None을 반환합니다.
+    pub fn from_report(report: &BacktestReport) -> Option<KellyStats> {
+        let wins = report.round_trip_returns.iter().filter(|r| **r > 0.0).collect::<Vec<&f64>>();
+        let losses = report.round_trip_returns.iter().filter(|r| **r <= 0.0).collect::<Vec<&f64>>();
+        if wins.is_empty() || losses.is_empty() {
+            return None;
+        }
+
+        let average_win = wins.iter().copied().sum::<f64>() / wins.len() as f64;
+        let average_loss = losses.iter().copied().sum::<f64>().abs() / losses.len() as f64;
+        if average_loss == 0.0 {
+            return None;
+        }
+
+        Some(KellyStats {
+            win_rate: wins.len() as f64 / report.round_trip_returns.len() as f64,
+            payoff_ratio: average_win / average_loss,
+        })
+    }
+
+    /// 켈리 비율 w - (1 - w) / R, 음수이면 기대값이 없으므로 0을 반환합니다.
+    pub fn kelly(&self) -> f64 {
+        (self.win_rate - (1.0 - self.win_rate) / self.payoff_ratio).max(0.0)
+    }
+}
+
+/// # 매수 금액 결정 방식
+/// - FixedFraction: 잔고의 일정 비율
+/// - VolatilityTarget: 평가액 × 목표 변동성 / 현재 변동성, 변동성이 클수록 적게 매수합니다.
+/// - FixedRisk: 손절 시 잃는 금액이 평가액의 risk%가 되도록 평가액 × risk / 손절 폭만큼 매수합니다.
+/// - Kelly: 평가액 × 켈리 비율 × fraction
+///
+/// 금액은 모두 매수할 마켓의 호가 통화 기준이며, 어떤 방식이든 매수 금액은 잔고를 넘지 않습니다.
+#[derive(Debug, Clone)]
+pub enum PositionSizing {
+    FixedFraction(f64),
+    VolatilityTarget { target: f64, measure: VolatilityMeasure },
+    FixedRisk { risk: f64, stop: StopDistance },
+    Kelly { fraction: f64, stats: KellyStats },
+}
+
+impl Default for PositionSizing {
+    fn default() -> Self {
+        PositionSizing::FixedFraction(0.2)
+    }
+}
+
+impl PositionSizing {
+    /// candle_data는 매수하려는 종목의 캔들 데이터이며, 최신 데이터가 앞에 옵니다.
+    /// 변동성이나 손절 폭을 구할 수 없으면 0을 반환하여 매수하지 않습니다.
+    pub fn position_size(&self, cash: f64, equity: f64, candle_data: &[CandleData]) -> f64 {
+        let size = match self {
+            PositionSizing::FixedFraction(fraction) => cash * fraction,
+            PositionSizing::VolatilityTarget { target, measure } => {
+                let volatility = measure.percent_of(candle_data).unwrap_or(0.0);
+                if volatility > 0.0 { equity * target / volatility } else { 0.0 }
+            }
+            PositionSizing::FixedRisk { risk, stop } => {
+                let stop_distance = stop.percent_of(candle_data).unwrap_or(0.0);
+                if stop_distance > 0.0 { equity * risk / stop_distance } else { 0.0 }
+            }
+            PositionSizing::Kelly { fraction, stats } => equity * stats.kelly() * fraction,
+        };
+
+        if size.is_finite() { size.clamp(0.0, cash) } else { 0.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 최신 데이터가 앞에 오는 종가 목록으로 캔들 데이터를 만듭니다. 고가와 저가는 종가와 같습니다.
+    fn candles(prices: &[f64]) -> Vec<CandleData> {
+        prices
+            .iter()
+            .map(|price| CandleData {
+                market: "KRW-TEST".to_string(),
+                candle_date_time_utc: String::new(),
+                candle_date_time_kst: String::new(),
+                opening_price: *price,
+                high_price: *price,
+                low_price: *price,
+                trade_price: *price,
+                timestamp: 0,
+                candle_acc_trade_price: *price,
+                candle_acc_trade_volume: 1.0,
+                unit: 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fixed_fraction_uses_cash() {
+        assert_eq!(PositionSizing::FixedFraction(0.2).position_size(1_000.0, 5_000.0, &candles(&[100.0])), 200.0);
+    }
+
+    #[test]
+    fn fixed_risk_divides_risk_by_stop_distance_and_caps_at_cash() {
+        let sizing = PositionSizing::FixedRisk { risk: 1.0, stop: StopDistance::Percent(2.0) };
+        assert_eq!(sizing.position_size(1_000.0, 1_000.0, &candles(&[100.0])), 500.0);
+        assert_eq!(sizing.position_size(300.0, 1_000.0, &candles(&[100.0])), 300.0);
+    }
+
+    #[test]
+    fn volatility_target_shrinks_with_volatility() {
+        // 모표준편차 1, 현재가 101 대비 100 / 101%
+        let sizing = PositionSizing::VolatilityTarget { target: 1.0, measure: VolatilityMeasure::Std };
+        let size = sizing.position_size(10_000.0, 1_000.0, &candles(&[101.0, 99.0]));
+        assert!((size - 1_010.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sizing_without_enough_candles_for_atr_buys_nothing() {
+        let volatility = PositionSizing::VolatilityTarget { target: 1.0, measure: VolatilityMeasure::Atr(14) };
+        assert_eq!(volatility.position_size(1_000.0, 1_000.0, &candles(&[100.0, 101.0])), 0.0);
+        let risk = PositionSizing::FixedRisk { risk: 1.0, stop: StopDistance::Atr { multiplier: 2.0, period: 14 } };
+        assert_eq!(risk.position_size(1_000.0, 1_000.0, &candles(&[100.0, 101.0])), 0.0);
+    }
+
+    #[test]
+    fn kelly_uses_win_rate_and_payoff_ratio() {
+        let report = BacktestReport {
+            market: "KRW-TEST".to_string(),
+            trades: Vec::new(),
+            initial_cash: 1_000.0,
+            final_equity: 1_000.0,
+            round_trip_returns: vec![4.0, 2.0, 6.0, -2.0, -2.0],
+        };
+        let stats = KellyStats::from_report(&report).unwrap();
+        // 승률 0.6, 평균 이익 4, 평균 손실 2
+        assert_eq!((stats.win_rate, stats.payoff_ratio), (0.6, 2.0));
+        assert!((stats.kelly() - 0.4).abs() < 1e-9);
+
+        let sizing = PositionSizing::Kelly { fraction: 0.5, stats };
+        assert!((sizing.position_size(1_000.0, 1_000.0, &candles(&[100.0])) - 200.0).abs() < 1e-9);
+
+        let losses_only = BacktestReport { round_trip_returns: vec![-1.0, -2.0], ..report };
+        assert!(KellyStats::from_report(&losses_only).is_none());
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use serde::Serialize;
+use tokio::sync::{broadcast, watch};
+use std::sync::Arc;
+use crate::upbit::journal::{now_millis, JournalEntry, TradeJournal};
+use crate::upbit::order_state::OrderStateManager;
+use crate::upbit::portfolio::PortfolioSnapshot;
+use crate::upbit::reconcile::ReconcileReport;
+use crate::upbit::risk::{PositionTracker, RiskBlock, RiskGuard};
+use crate::upbit::scoring::SignalScore;
+
+// 메모리에 보관할 평가액 기록의 최대 개수
+const EQUITY_HISTORY_LIMIT: usize = 10_000;
+// 메모리에 보관할 위험 한도 차단 기록의 최대 개수
+const RISK_BLOCK_LIMIT: usize = 1_000;
+
+/// # 서비스 상태
+/// 실행 중인 봇과 제어 API가 함께 참조하는 상태입니다.
+pub struct ServiceState {
+    mode: &'static str,
+    strategies: Vec<&'static str>,
+    paused: AtomicBool,
+    degraded: AtomicBool,
+    last_scan: Mutex<Option<i64>>,
+    scanned_markets: AtomicUsize,
+    failed_markets: AtomicUsize,
+    equity_history: Mutex<Vec<EquitySnapshot>>,
+    risk_blocks: Mutex<Vec<RiskBlockRecord>>,
+    candidates: Mutex<Vec<SignalScore>>,
+    unmanaged: Mutex<HashSet<String>>,
+    reconciliation: Mutex<Option<ReconcileReport>>,
+    events: broadcast::Sender<String>,
+    shutdown: watch::Sender<bool>,
+    pub journal: TradeJournal,
+    pub orders: Arc<OrderStateManager>,
+    pub tracker: PositionTracker,
+    pub risk: RiskGuard,
+}
+
+#[derive(Serialize)]
+pub struct StatusSnapshot {
+    pub mode: &'static str,
+    pub strategies: Vec<&'static str>,
+    pub paused: bool,
+    pub last_scan: Option<i64>,
+    pub scanned_markets: usize,
+    pub failed_markets: usize,
+    pub killed: bool,
+    pub degraded: bool,
+}
+
+/// 위험 한도에 걸려 주문하지 않은 기록
+#[derive(Serialize, Debug, Clone)]
+pub struct RiskBlockRecord {
+    pub timestamp: i64,
+    pub market: String,
+    pub reason: String,
+}
+
+/// 평가액 기록, 손익은 기록 시점까지의 누적값입니다.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct EquitySnapshot {
+    pub timestamp: i64,
+    pub equity: f64,
+    pub krw: f64,
+    pub holdings_value: f64,
+    pub unrealized_pnl: f64,
+    pub realized_pnl: f64,
+}
+
+impl ServiceState {
+    pub fn new(mode: &'static str, journal: TradeJournal) -> ServiceState {
+        let (events, _) = broadcast::channel(64);
+        let (shutdown, _) = watch::channel(false);
+        ServiceState {
+            mode,
+            strategies: vec!["rsi-divergence"],
+            paused: AtomicBool::new(false),
+            degraded: AtomicBool::new(false),
+            last_scan: Mutex::new(None),
+            scanned_markets: AtomicUsize::new(0),
+            failed_markets: AtomicUsize::new(0),
+            equity_history: Mutex::new(Vec::new()),
+            risk_blocks: Mutex::new(Vec::new()),
+            candidates: Mutex::new(Vec::new()),
+            unmanaged: Mutex::new(HashSet::new()),
+            reconciliation: Mutex::new(None),
+            events,
+            shutdown,
+            journal,
+            orders: Arc::new(OrderStateManager::default()),
+            tracker: PositionTracker::default(),
+            risk: RiskGuard::default(),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        self.publish("status", &self.snapshot());
+    }
+
+    /// 종료를 요청합니다. 이후 새 매매 신호는 처리되지 않습니다.
+    pub fn request_shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    pub fn shutdown_receiver(&self) -> watch::Receiver<bool> {
+        self.shutdown.subscribe()
+    }
+
+    pub fn mark_scanned(&self, scanned_markets: usize, failed_markets: usize) {
+        *self.last_scan.lock().unwrap() = Some(now_millis());
+        self.scanned_markets.store(scanned_markets, Ordering::SeqCst);
+        self.failed_markets.store(failed_markets, Ordering::SeqCst);
+        self.publish("status", &self.snapshot());
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::SeqCst)
+    }
+
+    /// 시세 조회가 원활하지 않은 상태인지 기록합니다. 이 상태에서는 새로 매수하지 않습니다.
+    pub fn set_degraded(&self, degraded: bool) {
+        if self.degraded.swap(degraded, Ordering::SeqCst) != degraded {
+            self.publish("status", &self.snapshot());
+        }
+    }
+
+    pub fn snapshot(&self) -> StatusSnapshot {
+        StatusSnapshot {
+            mode: self.mode,
+            strategies: self.strategies.clone(),
+            paused: self.is_paused(),
+            last_scan: *self.last_scan.lock().unwrap(),
+            scanned_markets: self.scanned_markets.load(Ordering::SeqCst),
+            failed_markets: self.failed_markets.load(Ordering::SeqCst),
+            killed: self.risk.is_killed(),
+            degraded: self.is_degraded(),
+        }
+    }
+
+    /// 킬 스위치를 켜거나 끕니다. 켜져 있는 동안 서비스는 새 주문 없이 모든 보유 종목을 청산합니다.
+    pub fn set_killed(&self, killed: bool) {
+        self.risk.set_killed(killed);
+        self.publish("status", &self.snapshot());
+    }
+
+    /// 위험 한도에 걸린 주문을 기록하고 대시보드에 알립니다.
+    /// 같은 종목이 같은 이유로 1분 안에 다시 차단되면 중복 기록하지 않습니다.
+    pub fn record_risk_block(&self, market: &str, block: &RiskBlock) {
+        let record = RiskBlockRecord {
+            timestamp: now_millis(),
+            market: market.to_string(),
+            reason: block.to_string(),
+        };
+        {
+            let mut risk_blocks = self.risk_blocks.lock().unwrap();
+            let duplicated = risk_blocks
+                .iter()
+                .rev()
+                .take_while(|previous| record.timestamp - previous.timestamp < 60_000)
+                .any(|previous| previous.market == record.market && previous.reason == record.reason);
+            if duplicated {
+                return;
+            }
+            if risk_blocks.len() >= RISK_BLOCK_LIMIT {
+                risk_blocks.remove(0);
+            }
+            risk_blocks.push(record.clone());
+        }
+        eprintln!("[위험 한도] {}: {}", record.market, record.reason);
+        self.publish("risk", &record);
+    }
+
+    pub fn risk_blocks(&self) -> Vec<RiskBlockRecord> {
+        self.risk_blocks.lock().unwrap().clone()
+    }
+
+    /// 마지막 스캔의 매수 후보 순위를 저장합니다.
+    pub fn set_candidates(&self, candidates: Vec<SignalScore>) {
+        *self.candidates.lock().unwrap() = candidates;
+    }
+
+    pub fn candidates(&self) -> Vec<SignalScore> {
+        self.candidates.lock().unwrap().clone()
+    }
+
+    /// 봇이 관리하지 않을 종목으로 표시합니다. 청산 규칙과 전략 매도에서 제외됩니다.
+    pub fn set_unmanaged(&self, market: &str) {
+        self.unmanaged.lock().unwrap().insert(market.to_string());
+    }
+
+    pub fn is_unmanaged(&self, market: &str) -> bool {
+        self.unmanaged.lock().unwrap().contains(market)
+    }
+
+    pub fn set_reconciliation(&self, report: ReconcileReport) {
+        *self.reconciliation.lock().unwrap() = Some(report);
+    }
+
+    pub fn reconciliation(&self) -> Option<ReconcileReport> {
+        self.reconciliation.lock().unwrap().clone()
+    }
+
+    /// 거래 일지에 기록하고 대시보드에 알립니다.
+    pub fn record_trade(&self, entry: JournalEntry) {
+        self.publish("trade", &entry);
+        self.journal.record(entry);
+    }
+
+    pub fn record_equity(&self, portfolio: &PortfolioSnapshot) {
+        let snapshot = EquitySnapshot {
+            timestamp: portfolio.timestamp,
+            equity: portfolio.total_equity,
+            krw: portfolio.krw,
+            holdings_value: portfolio.holdings_value,
+            unrealized_pnl: portfolio.unrealized_pnl,
+            realized_pnl: portfolio.realized_pnl,
+        };
+        {
+            let mut history = self.equity_history.lock().unwrap();
+            if history.len() >= EQUITY_HISTORY_LIMIT {
+                history.remove(0);
+            }
+            history.push(snapshot);
+        }
+        self.publish("equity", &snapshot);
+    }
+
+    pub fn equity_history(&self) -> Vec<EquitySnapshot> {
+        self.equity_history.lock().unwrap().clone()
+    }
+
+    /// server-sent events 형식으로 직렬화된 이벤트를 구독합니다.
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.events.subscribe()
+    }
+
+    fn publish<T: Serialize>(&self, kind: &str, payload: &T) {
+        if let Ok(data) = serde_json::to_string(payload) {
+            // 구독자가 없으면 실패하지만 무시해도 됩니다.
+            let _ = self.events.send(format!("event: {kind}\ndata: {data}\n\n"));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::upbit::ops::{BandOptions, DmiValue, MacdComponent, MacdCross, MacdOptions, RsiDivergenceCheckMode, latest_value};
+use crate::upbit::response::{CandleData, CandleDataOperation};
+use crate::upbit::trade_flow::{FlowConditions, TradeFlow};
+
+/// # 매수 조건 확인
+/// 5개 데이터 이내 RSI 30 미만 저점 다이버전스가 발생했고, 현재 가격이 평균보다 낮으면 true를 반환합니다.
+pub fn is_buy_signal(candle_data: &[CandleData]) -> bool {
+    candle_data.check_rsi_divergence(&RsiDivergenceCheckMode::Minpoint, &30.0, &5) // 5개 데이터 이내 RSI 다이버전스 발생
+        && candle_data.get_last_price() < candle_data.get_ewm_mean() // 현재 가격이 평균보다 낮음
+}
+
+/// # 매도 조건 확인
+/// RSI 70 초과 고점 다이버전스, RSI 꺾임, 또는 RSI가 올랐는데도 가격이 평균 아래인 경우 true를 반환합니다.
+pub fn is_sell_signal(candle_data: &[CandleData]) -> bool {
+    candle_data.check_rsi_divergence(&RsiDivergenceCheckMode::Peak, &70.0, &5) // 5개 데이터 이내 RSI 다이버전스 발생
+        || candle_data.check_rsi_breaking_peak(&4, &70.0) // RSI 꺾임 발생
+        || candle_data.get_rsi() > 60.0 && candle_data.get_last_price() < candle_data.get_ewm_mean() // RSI가 올랐는데도 가격이 오르지 않았으면 가망이 없는 종목이라 판단
+}
+
+/// # 체결 흐름 매수 조건 확인
+/// 매수 체결 비율 조건이 없거나, 최근 체결의 delta 비율이 조건 이상이면 true를 반환합니다.
+pub fn is_buy_flow(flow: &TradeFlow, conditions: &FlowConditions) -> bool {
+    conditions.min_buy_delta_ratio.is_none_or(|min| flow.delta_ratio >= min)
+}
+
+/// # 체결 흐름 매도 조건 확인
+/// 최근 체결에서 매도 체결이 조건 이상으로 우세하면 true를 반환합니다.
+pub fn is_sell_flow(flow: &TradeFlow, conditions: &FlowConditions) -> bool {
+    conditions.sell_delta_ratio.is_some_and(|ratio| -flow.delta_ratio >= ratio)
+}
+
+/// # 추가 매수 확인 조건
+/// RSI 다이버전스 매수 신호에 더해 만족해야 하는 지표 조건이며, 기본값은 추가 조건이 없습니다.
+/// - macd: MACD 기간
+/// - macd_cross_within: 이 캔들 수 이내의 마지막 MACD 교차가 골든 크로스여야 매수합니다.
+/// - macd_divergence: 5개 데이터 이내에 MACD 히스토그램 저점 다이버전스가 있어야 매수합니다.
+/// - bands: 볼린저 밴드와 켈트너 채널 설정
+/// - below_lower_band: 가격이 볼린저 밴드 하단보다 낮아야 매수합니다.
+/// - skip_squeeze: 볼린저 밴드가 켈트너 채널 안으로 수축한 동안에는 매수하지 않습니다.
+/// - dmi_period: ADX, +DI, -DI 기간
+/// - downtrend_adx: ADX가 이 값 이상이고 -DI가 +DI보다 큰 강한 하락 추세에서는 매수하지 않습니다.
+/// - volume_lookback: 상대 거래량을 계산할 직전 캔들 수
+/// - volume_spike: 다이버전스를 확인하는 5개 데이터 이내에 상대 거래량이 이 배수 이상인 캔들이 있어야 매수합니다.
+/// - obv_rising: 5개 데이터 전보다 OBV가 높아야 매수합니다.
+/// - mfi_period: MFI 기간
+/// - max_mfi: MFI가 이 값 이하여야 매수합니다.
+/// - below_vwap: 가격이 당일 VWAP보다 낮아야 매수합니다.
+#[derive(Debug, Clone)]
+pub struct Confirmations {
+    pub macd: MacdOptions,
+    pub macd_cross_within: Option<usize>,
+    pub macd_divergence: bool,
+    pub bands: BandOptions,
+    pub below_lower_band: bool,
+    pub skip_squeeze: bool,
+    pub dmi_period: usize,
+    pub downtrend_adx: Option<f64>,
+    pub volume_lookback: usize,
+    pub volume_spike: Option<f64>,
+    pub obv_rising: bool,
+    pub mfi_period: usize,
+    pub max_mfi: Option<f64>,
+    pub below_vwap: bool,
+}
+
+impl Default for Confirmations {
+    fn default() -> Self {
+        Confirmations {
+            macd: MacdOptions::default(),
+            macd_cross_within: None,
+            macd_divergence: false,
+            bands: BandOptions::default(),
+            below_lower_band: false,
+            skip_squeeze: false,
+            dmi_period: 14,
+            downtrend_adx: None,
+            volume_lookback: 20,
+            volume_spike: None,
+            obv_rising: false,
+            mfi_period: 14,
+            max_mfi: None,
+            below_vwap: false,
+        }
+    }
+}
+
+/// # 추가 매수 확인
+/// 설정된 확인 조건을 모두 만족하면 true를 반환합니다.
+pub fn is_buy_confirmed(candle_data: &[CandleData], confirmations: &Confirmations) -> bool {
+    confirmations.macd_cross_within.is_none_or(|within| candle_data.get_macd(&confirmations.macd).last_cross(within) == Some(MacdCross::Golden))
+        && (!confirmations.macd_divergence
+            || candle_data.check_macd_divergence(&confirmations.macd, &MacdComponent::Histogram, &RsiDivergenceCheckMode::Minpoint, &5))
+        && (!confirmations.below_lower_band || candle_data.check_below_lower_band(&confirmations.bands))
+        && (!confirmations.skip_squeeze || !candle_data.check_squeeze(&confirmations.bands))
+        && confirmations.downtrend_adx.is_none_or(|min_adx| !candle_data.get_dmi(confirmations.dmi_period).latest()
+            .is_some_and(|DmiValue { plus_di, minus_di, adx }| adx >= min_adx && minus_di > plus_di))
+        && confirmations.volume_spike.is_none_or(|multiple| candle_data.check_volume_spike(confirmations.volume_lookback, multiple, &5))
+        && (!confirmations.obv_rising || candle_data.check_obv_rising(&5))
+        && confirmations.max_mfi.is_none_or(|max_mfi| latest_value(&candle_data.get_mfi_series(confirmations.mfi_period)).is_some_and(|mfi| mfi <= max_mfi))
+        && (!confirmations.below_vwap
+            || latest_value(&candle_data.get_session_vwap_series()).is_some_and(|vwap| candle_data[0].trade_price < vwap))
+}
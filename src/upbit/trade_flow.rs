@@ -0,0 +1,94 @@
+use serde::Serialize;
+use crate::upbit::response::{TradeSide, TradeTick};
+
+/// # 체결 흐름 조건
+/// - ticks: 흐름을 계산할 최근 체결 수
+/// - large_trade_multiple: 평균 체결량의 이 배수 이상이면 대량 체결로 봅니다.
+/// - min_buy_delta_ratio: 매수 체결 비율이 이 값 이상일 때만 매수합니다. (-1 ~ 1)
+/// - sell_delta_ratio: 매도 체결 비율이 이 값 이상이면 보유 종목을 매도합니다. (0 ~ 1)
+#[derive(Debug, Clone)]
+pub struct FlowConditions {
+    pub ticks: usize,
+    pub large_trade_multiple: f64,
+    pub min_buy_delta_ratio: Option<f64>,
+    pub sell_delta_ratio: Option<f64>,
+}
+
+impl Default for FlowConditions {
+    fn default() -> Self {
+        FlowConditions {
+            ticks: 200,
+            large_trade_multiple: 10.0,
+            min_buy_delta_ratio: None,
+            sell_delta_ratio: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LargeTrade {
+    pub timestamp: i64,
+    pub side: TradeSide,
+    pub price: f64,
+    pub volume: f64,
+}
+
+/// # 체결 흐름
+/// 매수 체결은 매수자가 매도 호가를 가져간 체결, 매도 체결은 매도자가 매수 호가를 가져간 체결입니다.
+/// - delta: 매수 체결량 - 매도 체결량
+/// - delta_ratio: delta / 전체 체결량, -1에서 1 사이입니다.
+/// - cumulative_delta: 오래된 체결부터 누적한 delta
+/// - large_trade_delta: 대량 체결 중 매수 체결량 - 매도 체결량
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TradeFlow {
+    pub buy_volume: f64,
+    pub sell_volume: f64,
+    pub delta: f64,
+    pub delta_ratio: f64,
+    pub cumulative_delta: Vec<f64>,
+    pub large_trades: Vec<LargeTrade>,
+    pub large_trade_delta: f64,
+}
+
+fn signed_volume(tick: &TradeTick) -> f64 {
+    match tick.ask_bid {
+        TradeSide::Bid => tick.trade_volume,
+        TradeSide::Ask => -tick.trade_volume,
+    }
+}
+
+impl TradeFlow {
+    /// ticks는 최신 체결이 앞에 옵니다.
+    pub fn from_ticks(ticks: &[TradeTick], large_trade_multiple: f64) -> TradeFlow {
+        if ticks.is_empty() {
+            return TradeFlow::default();
+        }
+
+        let mut flow = TradeFlow::default();
+        let mut cumulative = 0.0;
+        for tick in ticks.iter().rev() {
+            match tick.ask_bid {
+                TradeSide::Bid => flow.buy_volume += tick.trade_volume,
+                TradeSide::Ask => flow.sell_volume += tick.trade_volume,
+            }
+            cumulative += signed_volume(tick);
+            flow.cumulative_delta.push(cumulative);
+        }
+
+        let total = flow.buy_volume + flow.sell_volume;
+        flow.delta = flow.buy_volume - flow.sell_volume;
+        flow.delta_ratio = if total > 0.0 { flow.delta / total } else { 0.0 };
+
+        let threshold = total / ticks.len() as f64 * large_trade_multiple;
+        for tick in ticks.iter().filter(|tick| tick.trade_volume >= threshold) {
+            flow.large_trade_delta += signed_volume(tick);
+            flow.large_trades.push(LargeTrade {
+                timestamp: tick.timestamp,
+                side: tick.ask_bid,
+                price: tick.trade_price,
+                volume: tick.trade_volume,
+            });
+        }
+        flow
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+use std::path::Path;
+use serde::Deserialize;
+use crate::upbit::response::{WalletState, WalletStatus};
+
+/// # 출금 허용 목록
+/// 코인 출금은 여기 적힌 (통화, 네트워크, 주소, 보조 주소)로만 보낼 수 있고, 원화 출금은 allow_krw가 true일 때만 가능합니다.
+/// 파일 경로는 `YIPIR_WITHDRAW_ALLOWLIST`로 지정하며, 지정하지 않으면 어떤 출금도 보내지 않습니다.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct WithdrawalAllowlist {
+    #[serde(default)]
+    pub addresses: Vec<AllowedAddress>,
+    #[serde(default)]
+    pub allow_krw: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AllowedAddress {
+    pub currency: String,
+    pub net_type: String,
+    pub address: String,
+    // 데스티네이션 태그나 메모가 필요한 통화의 보조 주소
+    #[serde(default)]
+    pub secondary_address: Option<String>,
+}
+
+/// # 코인 출금 요청
+/// net_type은 출금 네트워크로, 출금 가능 정보의 wallet_support에서 고릅니다.
+#[derive(Debug, Clone)]
+pub struct CoinWithdrawal {
+    pub currency: String,
+    pub net_type: String,
+    pub amount: f64,
+    pub address: String,
+    pub secondary_address: Option<String>,
+}
+
+impl WithdrawalAllowlist {
+    pub fn load(path: &Path) -> Result<WithdrawalAllowlist, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("출금 허용 목록 파일을 읽을 수 없습니다: {e}"))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("출금 허용 목록 파일 형식이 잘못되었습니다: {e}"))
+    }
+
+    /// `YIPIR_WITHDRAW_ALLOWLIST`에 지정한 파일을 읽습니다.
+    pub fn from_env() -> Result<WithdrawalAllowlist, String> {
+        match std::env::var("YIPIR_WITHDRAW_ALLOWLIST") {
+            Ok(path) => WithdrawalAllowlist::load(Path::new(&path)),
+            Err(_) => Err("YIPIR_WITHDRAW_ALLOWLIST가 설정되지 않아 출금할 수 없습니다.".to_string()),
+        }
+    }
+
+    /// 통화, 네트워크, 주소, 보조 주소가 모두 일치하는 항목이 있어야 허용합니다.
+    pub fn permits(&self, withdrawal: &CoinWithdrawal) -> bool {
+        self.addresses.iter().any(|allowed| {
+            allowed.currency == withdrawal.currency
+                && allowed.net_type == withdrawal.net_type
+                && allowed.address == withdrawal.address
+                && allowed.secondary_address == withdrawal.secondary_address
+        })
+    }
+}
+
+/// # 입출금 중단 통화
+/// 네트워크가 여럿인 통화는 하나라도 입출금이 모두 가능하면 정상으로 봅니다.
+pub fn suspended_currencies(statuses: &[WalletStatus]) -> HashSet<String> {
+    let working = statuses
+        .iter()
+        .filter(|status| status.wallet_state == WalletState::Working)
+        .map(|status| status.currency.as_str())
+        .collect::<HashSet<&str>>();
+    statuses
+        .iter()
+        .filter(|status| !working.contains(status.currency.as_str()))
+        .map(|status| status.currency.clone())
+        .collect()
+}